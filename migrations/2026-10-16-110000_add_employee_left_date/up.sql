@@ -0,0 +1,3 @@
+-- Your SQL goes here
+
+ALTER TABLE employees ADD COLUMN left_date TIMESTAMP;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+
+CREATE UNIQUE INDEX projects_lower_name_key ON projects (lower(name));
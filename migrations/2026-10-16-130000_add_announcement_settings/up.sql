@@ -0,0 +1,11 @@
+-- Your SQL goes here
+
+CREATE TABLE announcement_settings (
+    id INTEGER PRIMARY KEY DEFAULT 1 CHECK (id = 1),
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    channel_id VARCHAR,
+    template VARCHAR NOT NULL DEFAULT '¡Démosle la bienvenida a {usuario}, que se suma desde {pais}!',
+    intro VARCHAR
+);
+
+INSERT INTO announcement_settings DEFAULT VALUES;
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+
+DROP TABLE task_completions;
+DROP TABLE checklist_tasks;
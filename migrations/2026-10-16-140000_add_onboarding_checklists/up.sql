@@ -0,0 +1,21 @@
+-- Your SQL goes here
+
+CREATE TABLE checklist_tasks (
+    id UUID NOT NULL DEFAULT uuid_generate_v4(),
+    project_id UUID NOT NULL,
+    title VARCHAR NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (id),
+    UNIQUE (project_id, position),
+    FOREIGN KEY (project_id) REFERENCES projects(id)
+);
+
+CREATE TABLE task_completions (
+    task_id UUID NOT NULL,
+    employee_id VARCHAR NOT NULL,
+    completed_by VARCHAR NOT NULL,
+    completed_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (task_id, employee_id),
+    FOREIGN KEY (task_id) REFERENCES checklist_tasks(id),
+    FOREIGN KEY (employee_id) REFERENCES employees(id)
+);
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+
+CREATE TABLE buddies (
+    employee_id VARCHAR NOT NULL,
+    buddy_id VARCHAR NOT NULL,
+    assigned_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (employee_id),
+    CHECK (employee_id <> buddy_id),
+    FOREIGN KEY (employee_id) REFERENCES employees(id),
+    FOREIGN KEY (buddy_id) REFERENCES employees(id)
+);
+
+CREATE INDEX buddies_buddy_id_idx ON buddies (buddy_id);
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+
+ALTER TABLE onboardees ALTER COLUMN onboarding_date TYPE TIMESTAMP;
+ALTER TABLE employees ALTER COLUMN left_date TYPE TIMESTAMP;
+
+ALTER TABLE task_completions ALTER COLUMN completed_at TYPE TIMESTAMP USING completed_at AT TIME ZONE 'UTC';
+ALTER TABLE buddies ALTER COLUMN assigned_at TYPE TIMESTAMP USING assigned_at AT TIME ZONE 'UTC';
+ALTER TABLE employees ALTER COLUMN join_date TYPE TIMESTAMP USING join_date AT TIME ZONE 'UTC';
@@ -0,0 +1,10 @@
+-- Your SQL goes here
+
+-- Instants were stored as naive UTC.
+ALTER TABLE employees ALTER COLUMN join_date TYPE TIMESTAMPTZ USING join_date AT TIME ZONE 'UTC';
+ALTER TABLE buddies ALTER COLUMN assigned_at TYPE TIMESTAMPTZ USING assigned_at AT TIME ZONE 'UTC';
+ALTER TABLE task_completions ALTER COLUMN completed_at TYPE TIMESTAMPTZ USING completed_at AT TIME ZONE 'UTC';
+
+-- Leaving and onboarding are days admins type, which read the same anywhere.
+ALTER TABLE employees ALTER COLUMN left_date TYPE DATE;
+ALTER TABLE onboardees ALTER COLUMN onboarding_date TYPE DATE;
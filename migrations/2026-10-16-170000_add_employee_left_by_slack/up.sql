@@ -0,0 +1,4 @@
+-- Your SQL goes here
+
+-- Slack deactivations are undone by Slack; offboardings from /baja are not.
+ALTER TABLE employees ADD COLUMN left_by_slack BOOLEAN NOT NULL DEFAULT FALSE;
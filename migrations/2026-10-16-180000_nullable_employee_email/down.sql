@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+
+-- Fails while any employee has no email.
+ALTER TABLE employees ALTER COLUMN email SET NOT NULL;
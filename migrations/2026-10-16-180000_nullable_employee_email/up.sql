@@ -0,0 +1,4 @@
+-- Your SQL goes here
+
+-- Slack has no email for some members; NULLs don't clash on the UNIQUE constraint.
+ALTER TABLE employees ALTER COLUMN email DROP NOT NULL;
@@ -1,14 +1,199 @@
 #[cfg(test)]
 mod tests;
 
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rocket::{
+    data::{ByteUnit, Data, FromData, Limits, Outcome},
+    form::{Form, FromForm},
+    http::{RawStr, Status},
+    request::Request,
+};
+use serde::de::DeserializeOwned;
+use sha2::Sha256;
 use std::env;
 
-pub fn authenticate(token: &str, api_app_id: &str) -> Result<(), String> {
-    let env_slack_token = env::var("SLACK_TOKEN").unwrap();
-    let env_slack_api_app_id = env::var("SLACK_API_APP_ID").unwrap();
+type HmacSha256 = Hmac<Sha256>;
 
-    if token != env_slack_token || api_app_id != env_slack_api_app_id {
-        return Err("Invalid token or app_id".to_string());
+pub const SIGNATURE_HEADER: &str = "X-Slack-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Slack-Request-Timestamp";
+const SIGNATURE_VERSION: &str = "v0";
+const MAX_REQUEST_AGE_SECS: i64 = 5 * 60;
+
+/// Slack app signing secret, kept in Rocket state for the body guards.
+pub struct SigningSecret(pub String);
+
+impl SigningSecret {
+    pub fn from_env() -> Self {
+        SigningSecret(env::var("SLACK_SIGNING_SECRET").expect("SLACK_SIGNING_SECRET must be set"))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SlackRequestError {
+    MissingSecret,
+    MissingHeader(&'static str),
+    InvalidTimestamp(String),
+    Expired(i64),
+    InvalidSignature,
+    TooLarge,
+    Io(String),
+    Parse(String),
+}
+
+impl SlackRequestError {
+    fn status(&self) -> Status {
+        match self {
+            SlackRequestError::MissingSecret => Status::InternalServerError,
+            SlackRequestError::TooLarge => Status::PayloadTooLarge,
+            SlackRequestError::Io(_) => Status::BadRequest,
+            SlackRequestError::Parse(_) => Status::UnprocessableEntity,
+            _ => Status::Unauthorized,
+        }
+    }
+}
+
+/// Checks a request against Slack's `v0` signing scheme: the signature must be
+/// the HMAC-SHA256 of `v0:{timestamp}:{body}` keyed with the signing secret,
+/// and the timestamp must be within five minutes of `now`.
+pub fn verify_signature(
+    secret: &str,
+    timestamp: &str,
+    signature: &str,
+    body: &str,
+    now: i64,
+) -> Result<(), SlackRequestError> {
+    let ts: i64 = timestamp
+        .parse()
+        .map_err(|_| SlackRequestError::InvalidTimestamp(timestamp.to_string()))?;
+
+    if (now - ts).abs() > MAX_REQUEST_AGE_SECS {
+        return Err(SlackRequestError::Expired(ts));
+    }
+
+    let expected = signature
+        .strip_prefix(&format!("{}=", SIGNATURE_VERSION))
+        .and_then(|hex_sig| hex::decode(hex_sig).ok())
+        .ok_or(SlackRequestError::InvalidSignature)?;
+
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .map_err(|_| SlackRequestError::MissingSecret)?;
+    mac.update(format!("{}:{}:{}", SIGNATURE_VERSION, timestamp, body).as_bytes());
+
+    // `verify_slice` compares in constant time.
+    mac.verify_slice(&expected)
+        .map_err(|_| SlackRequestError::InvalidSignature)
+}
+
+/// Signs `body` the way Slack does, for building requests in tests.
+#[cfg(test)]
+pub fn sign(secret: &str, timestamp: &str, body: &str) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(format!("{}:{}:{}", SIGNATURE_VERSION, timestamp, body).as_bytes());
+    format!(
+        "{}={}",
+        SIGNATURE_VERSION,
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
+
+async fn read_signed_body<'r>(
+    req: &'r Request<'_>,
+    data: Data<'r>,
+    limit: ByteUnit,
+) -> Result<String, SlackRequestError> {
+    let secret = req
+        .rocket()
+        .state::<SigningSecret>()
+        .ok_or(SlackRequestError::MissingSecret)?;
+    let timestamp = req
+        .headers()
+        .get_one(TIMESTAMP_HEADER)
+        .ok_or(SlackRequestError::MissingHeader(TIMESTAMP_HEADER))?;
+    let signature = req
+        .headers()
+        .get_one(SIGNATURE_HEADER)
+        .ok_or(SlackRequestError::MissingHeader(SIGNATURE_HEADER))?;
+
+    let body = match data.open(limit).into_string().await {
+        Ok(s) if s.is_complete() => s.into_inner(),
+        Ok(_) => return Err(SlackRequestError::TooLarge),
+        Err(e) => return Err(SlackRequestError::Io(e.to_string())),
+    };
+
+    verify_signature(
+        &secret.0,
+        timestamp,
+        signature,
+        &body,
+        Utc::now().timestamp(),
+    )?;
+
+    Ok(body)
+}
+
+/// JSON body guard that only succeeds for requests signed by Slack.
+#[derive(Debug)]
+pub struct SignedJson<T>(pub T);
+
+impl<T> SignedJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, T: DeserializeOwned> FromData<'r> for SignedJson<T> {
+    type Error = SlackRequestError;
+
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r, Self> {
+        let limit = req.limits().get("json").unwrap_or(Limits::JSON);
+
+        let result = read_signed_body(req, data, limit).await.and_then(|body| {
+            serde_json::from_str(&body)
+                .map(SignedJson)
+                .map_err(|e| SlackRequestError::Parse(e.to_string()))
+        });
+
+        match result {
+            Ok(value) => Outcome::Success(value),
+            Err(e) => {
+                println!("Rejected Slack request: {:?}", e);
+                Outcome::Error((e.status(), e))
+            }
+        }
+    }
+}
+
+/// Form body guard that only succeeds for requests signed by Slack.
+#[derive(Debug)]
+pub struct SignedForm<T>(pub T);
+
+impl<T> SignedForm<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, T: for<'a> FromForm<'a> + 'static> FromData<'r> for SignedForm<T> {
+    type Error = SlackRequestError;
+
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r, Self> {
+        let limit = req.limits().get("form").unwrap_or(Limits::FORM);
+
+        let result = read_signed_body(req, data, limit).await.and_then(|body| {
+            Form::<T>::parse_encoded(RawStr::new(&body))
+                .map(SignedForm)
+                .map_err(|e| SlackRequestError::Parse(e.to_string()))
+        });
+
+        match result {
+            Ok(value) => Outcome::Success(value),
+            Err(e) => {
+                println!("Rejected Slack request: {:?}", e);
+                Outcome::Error((e.status(), e))
+            }
+        }
     }
-    Ok(())
 }
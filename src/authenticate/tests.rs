@@ -1,27 +1,139 @@
 #[cfg(test)]
 mod test_authenticate {
-    use std::env;
+    use chrono::Utc;
+    use rocket::{
+        http::{ContentType, Header, Status},
+        local::blocking::Client,
+        serde::json::Value,
+    };
 
-    use crate::authenticate::authenticate;
+    use crate::authenticate::{
+        sign, verify_signature, SignedForm, SignedJson, SigningSecret, SlackRequestError,
+        SIGNATURE_HEADER, TIMESTAMP_HEADER,
+    };
+
+    // Recorded from Slack's request signing documentation.
+    const SECRET: &str = "8f742231b10e8888abcd99yyyzzz85a5";
+    const TIMESTAMP: &str = "1531420618";
+    const SIGNATURE: &str = "v0=a2114d57b48eac39b9ad189dd8316235a7b4a8d21a10bd27519666489c69b503";
+    const FORM_BODY: &str = "token=xyzz0WbapA4vBCDEFasx0q6G&team_id=T1DC2JH3J&team_domain=testteamnow&channel_id=G8PSS9T3V&channel_name=foobar&user_id=U2CERLKJA&user_name=roadrunner&command=%2Fwebhook-collect&text=&response_url=https%3A%2F%2Fhooks.slack.com%2Fcommands%2FT1DC2JH3J%2F397700885554%2F96rGlfmibIGlgcZRskXaIFfN&trigger_id=398738663015.47445629121.803a0bc887a14d10d2c447fce8b6703c";
+    const JSON_BODY: &str = r#"{"token":"Jhj5dZrVaK7ZwHHjRyZWjbDl","challenge":"3eZbrw1aBm2rZgRNFdxV2595E9CY3gmdALWMmHkvFXO7tYXAYM8P","type":"url_verification"}"#;
+
+    #[derive(FromForm)]
+    struct Command {
+        command: String,
+    }
+
+    #[post("/json", data = "<body>")]
+    fn json_route(body: SignedJson<Value>) -> String {
+        body.into_inner()["type"].as_str().unwrap().to_string()
+    }
+
+    #[post("/form", data = "<body>")]
+    fn form_route(body: SignedForm<Command>) -> String {
+        body.into_inner().command
+    }
+
+    fn client() -> Client {
+        let rocket = rocket::build()
+            .manage(SigningSecret(SECRET.to_string()))
+            .mount("/", routes![json_route, form_route]);
+        Client::tracked(rocket).unwrap()
+    }
 
     #[test]
-    fn should_pass_if_called_with_correct_params() {
-        let token = "some_token";
-        let api_app_id = "some_app_id";
-        env::set_var("SLACK_TOKEN", token);
-        env::set_var("SLACK_API_APP_ID", api_app_id);
+    fn should_accept_recorded_signature() {
+        let now = TIMESTAMP.parse::<i64>().unwrap() + 10;
+
+        let res = verify_signature(SECRET, TIMESTAMP, SIGNATURE, FORM_BODY, now);
 
-        authenticate(token, api_app_id).unwrap();
+        assert_eq!(res, Ok(()));
     }
 
     #[test]
-    fn should_fail_if_called_with_incorrect_params() {
-        let token = "some_token";
-        let api_app_id = "some_app_id";
-        env::set_var("SLACK_TOKEN", token);
-        env::set_var("SLACK_API_APP_ID", api_app_id);
-
-        let res = authenticate("asdas", "123");
-        assert_eq!(res, Err("Invalid token or app_id".to_string()));
+    fn should_reject_tampered_body() {
+        let now = TIMESTAMP.parse::<i64>().unwrap();
+        let tampered = FORM_BODY.replace("roadrunner", "coyote");
+
+        let res = verify_signature(SECRET, TIMESTAMP, SIGNATURE, &tampered, now);
+
+        assert_eq!(res, Err(SlackRequestError::InvalidSignature));
+    }
+
+    #[test]
+    fn should_reject_wrong_secret() {
+        let now = TIMESTAMP.parse::<i64>().unwrap();
+
+        let res = verify_signature("other_secret", TIMESTAMP, SIGNATURE, FORM_BODY, now);
+
+        assert_eq!(res, Err(SlackRequestError::InvalidSignature));
+    }
+
+    #[test]
+    fn should_reject_replays_older_than_five_minutes() {
+        let ts = TIMESTAMP.parse::<i64>().unwrap();
+
+        let res = verify_signature(SECRET, TIMESTAMP, SIGNATURE, FORM_BODY, ts + 5 * 60 + 1);
+
+        assert_eq!(res, Err(SlackRequestError::Expired(ts)));
+    }
+
+    #[test]
+    fn should_reject_malformed_signatures_and_timestamps() {
+        let now = TIMESTAMP.parse::<i64>().unwrap();
+
+        for signature in ["", "v0=", "v0=zz", "v1=a2114d57", &SIGNATURE[3..]] {
+            let res = verify_signature(SECRET, TIMESTAMP, signature, FORM_BODY, now);
+            assert_eq!(res, Err(SlackRequestError::InvalidSignature));
+        }
+
+        let res = verify_signature(SECRET, "abc", SIGNATURE, FORM_BODY, now);
+        assert_eq!(
+            res,
+            Err(SlackRequestError::InvalidTimestamp("abc".to_string()))
+        );
+    }
+
+    #[test]
+    fn guards_should_accept_signed_requests() {
+        let client = client();
+        let ts = Utc::now().timestamp().to_string();
+
+        let res = client
+            .post("/json")
+            .header(ContentType::JSON)
+            .header(Header::new(TIMESTAMP_HEADER, ts.clone()))
+            .header(Header::new(SIGNATURE_HEADER, sign(SECRET, &ts, JSON_BODY)))
+            .body(JSON_BODY)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!(res.into_string().unwrap(), "url_verification");
+
+        let res = client
+            .post("/form")
+            .header(ContentType::Form)
+            .header(Header::new(TIMESTAMP_HEADER, ts.clone()))
+            .header(Header::new(SIGNATURE_HEADER, sign(SECRET, &ts, FORM_BODY)))
+            .body(FORM_BODY)
+            .dispatch();
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!(res.into_string().unwrap(), "/webhook-collect");
+    }
+
+    #[test]
+    fn guards_should_reject_unsigned_or_stale_requests() {
+        let client = client();
+
+        let res = client.post("/json").body(JSON_BODY).dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
+
+        // Correctly signed, but recorded more than five minutes ago.
+        let res = client
+            .post("/form")
+            .header(Header::new(TIMESTAMP_HEADER, TIMESTAMP))
+            .header(Header::new(SIGNATURE_HEADER, SIGNATURE))
+            .body(FORM_BODY)
+            .dispatch();
+        assert_eq!(res.status(), Status::Unauthorized);
     }
 }
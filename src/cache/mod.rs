@@ -0,0 +1,289 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    models::Employee,
+    pg_database::{DbError, EmployeeFilter},
+    utils::load_env::redis_url,
+};
+use chrono::{DateTime, Utc};
+use redis::{Client, Commands};
+use rocket::tokio::task::spawn_blocking;
+use std::{env, fmt, sync::Arc, time::Duration};
+
+const KEY_PREFIX: &str = "onboarding_bot:nuevos";
+const DEFAULT_TTL_SECS: u64 = 5 * 60;
+const CONNECTION_TIMEOUT_SECS: u64 = 2;
+
+#[derive(Debug, PartialEq)]
+pub enum CacheError {
+    Connection(String),
+    Encoding(String),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CacheError::Connection(detail) => write!(f, "connection failure: {}", detail),
+            CacheError::Encoding(detail) => write!(f, "invalid entry: {}", detail),
+        }
+    }
+}
+
+impl std::error::Error for CacheError {}
+
+impl From<redis::RedisError> for CacheError {
+    fn from(e: redis::RedisError) -> Self {
+        CacheError::Connection(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for CacheError {
+    fn from(e: serde_json::Error) -> Self {
+        CacheError::Encoding(e.to_string())
+    }
+}
+
+/// A `get_employee_by_ts_range` query, reduced to what its result depends on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeKey {
+    from_ts: i64,
+    to_ts: i64,
+    include_inactive: bool,
+    countries: Vec<String>,
+}
+
+impl RangeKey {
+    pub fn new(from: DateTime<Utc>, to: DateTime<Utc>, filter: &EmployeeFilter) -> Self {
+        RangeKey {
+            from_ts: from.timestamp(),
+            to_ts: to.timestamp(),
+            include_inactive: filter.include_inactive,
+            countries: {
+                let mut countries = filter.countries.clone();
+                countries.sort();
+                countries.dedup();
+                countries
+            },
+        }
+    }
+}
+
+impl fmt::Display for RangeKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            self.from_ts,
+            self.to_ts,
+            self.include_inactive,
+            self.countries.join(",")
+        )
+    }
+}
+
+/// Results of `/nuevos` queries. Anything writing to `employees` must call
+/// `invalidate`, which moves on to a new generation. Entries are read and
+/// written under a generation, so rows loaded before an invalidation can't be
+/// served after it.
+pub trait EmployeeCache: Send + Sync {
+    fn generation(&self) -> Result<u64, CacheError>;
+
+    fn get(&self, generation: u64, key: &RangeKey) -> Result<Option<Vec<Employee>>, CacheError>;
+
+    fn set(
+        &self,
+        generation: u64,
+        key: &RangeKey,
+        employees: &[Employee],
+    ) -> Result<(), CacheError>;
+
+    fn invalidate(&self) -> Result<(), CacheError>;
+}
+
+/// Shared handle kept in Rocket state.
+pub type SharedEmployeeCache = Arc<dyn EmployeeCache>;
+
+/// Answers from the cache when possible, otherwise runs `load` and caches its
+/// result. Cache failures are logged and fall back to `load`.
+pub fn get_or_load<F>(
+    cache: &dyn EmployeeCache,
+    key: &RangeKey,
+    load: F,
+) -> Result<Vec<Employee>, DbError>
+where
+    F: FnOnce() -> Result<Vec<Employee>, DbError>,
+{
+    // Read before loading: if the table changes while we load, the result is
+    // stored under the old generation and never read again.
+    let generation = match cache.generation() {
+        Ok(generation) => generation,
+        Err(e) => {
+            println!("Cache read of {} failed: {}", key, e);
+            return load();
+        }
+    };
+
+    match cache.get(generation, key) {
+        Ok(Some(employees)) => return Ok(employees),
+        Ok(None) => {}
+        Err(e) => println!("Cache read of {} failed: {}", key, e),
+    }
+
+    let employees = load()?;
+    if let Err(e) = cache.set(generation, key, &employees) {
+        println!("Cache write of {} failed: {}", key, e);
+    }
+    Ok(employees)
+}
+
+/// Logs instead of failing, since a write already went through.
+pub fn invalidate(cache: &dyn EmployeeCache) {
+    if let Err(e) = cache.invalidate() {
+        println!("Cache invalidation failed: {}", e);
+    }
+}
+
+/// Invalidates from a blocking task, so callers answering Slack don't wait on
+/// Redis.
+pub fn spawn_invalidate(cache: &SharedEmployeeCache) {
+    let cache = cache.clone();
+    spawn_blocking(move || invalidate(cache.as_ref()));
+}
+
+/// Entries expire after `NUEVOS_CACHE_TTL_SECS`. Invalidating bumps a
+/// generation that is part of every key, so stale entries are never read
+/// again and just wait for their TTL.
+pub struct RedisEmployeeCache {
+    client: Client,
+    ttl_secs: u64,
+}
+
+impl RedisEmployeeCache {
+    pub fn from_env() -> Self {
+        let ttl_secs = env::var("NUEVOS_CACHE_TTL_SECS")
+            .map(|v| {
+                v.parse()
+                    .expect("NUEVOS_CACHE_TTL_SECS is not a valid number")
+            })
+            .unwrap_or(DEFAULT_TTL_SECS);
+
+        RedisEmployeeCache {
+            client: Client::open(redis_url()).expect("Invalid Redis URL"),
+            ttl_secs,
+        }
+    }
+
+    fn connection(&self) -> Result<redis::Connection, CacheError> {
+        self.client
+            .get_connection_with_timeout(Duration::from_secs(CONNECTION_TIMEOUT_SECS))
+            .map_err(CacheError::from)
+    }
+
+    fn generation_key() -> String {
+        format!("{}:generation", KEY_PREFIX)
+    }
+
+    fn entry_key(generation: u64, key: &RangeKey) -> String {
+        format!("{}:{}:{}", KEY_PREFIX, generation, key)
+    }
+}
+
+impl EmployeeCache for RedisEmployeeCache {
+    fn generation(&self) -> Result<u64, CacheError> {
+        let generation: Option<u64> = self.connection()?.get(Self::generation_key())?;
+        Ok(generation.unwrap_or(0))
+    }
+
+    fn get(&self, generation: u64, key: &RangeKey) -> Result<Option<Vec<Employee>>, CacheError> {
+        let entry: Option<String> = self.connection()?.get(Self::entry_key(generation, key))?;
+
+        match entry {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn set(
+        &self,
+        generation: u64,
+        key: &RangeKey,
+        employees: &[Employee],
+    ) -> Result<(), CacheError> {
+        let json = serde_json::to_string(employees)?;
+
+        self.connection()?
+            .set_ex::<_, _, ()>(Self::entry_key(generation, key), json, self.ttl_secs)
+            .map_err(CacheError::from)
+    }
+
+    fn invalidate(&self) -> Result<(), CacheError> {
+        self.connection()?
+            .incr::<_, _, ()>(Self::generation_key(), 1)
+            .map_err(CacheError::from)
+    }
+}
+
+/// In-process `EmployeeCache` for tests.
+#[cfg(test)]
+pub struct MemoryEmployeeCache {
+    ttl: Duration,
+    generation: std::sync::atomic::AtomicU64,
+    entries: std::sync::Mutex<std::collections::HashMap<(u64, RangeKey), MemoryEntry>>,
+}
+
+/// When an entry was stored, and its rows.
+#[cfg(test)]
+type MemoryEntry = (std::time::Instant, Vec<Employee>);
+
+#[cfg(test)]
+impl MemoryEmployeeCache {
+    pub fn with_ttl(ttl: Duration) -> Self {
+        MemoryEmployeeCache {
+            ttl,
+            generation: Default::default(),
+            entries: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Default for MemoryEmployeeCache {
+    fn default() -> Self {
+        MemoryEmployeeCache::with_ttl(Duration::from_secs(DEFAULT_TTL_SECS))
+    }
+}
+
+#[cfg(test)]
+impl EmployeeCache for MemoryEmployeeCache {
+    fn generation(&self) -> Result<u64, CacheError> {
+        Ok(self.generation.load(std::sync::atomic::Ordering::SeqCst))
+    }
+
+    fn get(&self, generation: u64, key: &RangeKey) -> Result<Option<Vec<Employee>>, CacheError> {
+        let entries = self.entries.lock().unwrap();
+        Ok(entries
+            .get(&(generation, key.clone()))
+            .filter(|(stored_at, _)| stored_at.elapsed() < self.ttl)
+            .map(|(_, employees)| employees.clone()))
+    }
+
+    fn set(
+        &self,
+        generation: u64,
+        key: &RangeKey,
+        employees: &[Employee],
+    ) -> Result<(), CacheError> {
+        self.entries.lock().unwrap().insert(
+            (generation, key.clone()),
+            (std::time::Instant::now(), employees.to_vec()),
+        );
+        Ok(())
+    }
+
+    fn invalidate(&self) -> Result<(), CacheError> {
+        self.generation
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod test_employee_cache {
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    use chrono::NaiveDate;
+
+    use crate::cache::{get_or_load, EmployeeCache, MemoryEmployeeCache, RangeKey};
+    use crate::models::test_employee;
+    use crate::pg_database::{DbError, EmployeeFilter};
+
+    fn key(include_inactive: bool) -> RangeKey {
+        key_with_filter(&EmployeeFilter {
+            include_inactive,
+            ..Default::default()
+        })
+    }
+
+    fn key_with_filter(filter: &EmployeeFilter) -> RangeKey {
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc();
+        let to = NaiveDate::from_ymd_opt(2024, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 59, 59)
+            .unwrap()
+            .and_utc();
+        RangeKey::new(from, to, filter)
+    }
+
+    #[test]
+    fn should_format_key_from_timestamps_and_filter() {
+        assert_eq!(key(false).to_string(), "1704067200:1735689599:false:");
+    }
+
+    #[test]
+    fn should_not_depend_on_country_order() {
+        let key = key_with_filter(&EmployeeFilter {
+            include_inactive: false,
+            countries: vec!["chile".to_string(), "argentina".to_string()],
+        });
+        let same_key = key_with_filter(&EmployeeFilter {
+            include_inactive: false,
+            countries: vec!["argentina".to_string(), "chile".to_string()],
+        });
+
+        assert_eq!(key, same_key);
+        assert_eq!(
+            key.to_string(),
+            "1704067200:1735689599:false:argentina,chile"
+        );
+    }
+
+    #[test]
+    fn should_only_load_on_cache_miss() {
+        let cache = MemoryEmployeeCache::default();
+        let loads = Cell::new(0);
+        let load = || {
+            loads.set(loads.get() + 1);
+            Ok(vec![test_employee("ABC123", 1706745600)])
+        };
+
+        let first = get_or_load(&cache, &key(false), load).unwrap();
+        let second = get_or_load(&cache, &key(false), load).unwrap();
+        get_or_load(&cache, &key(true), load).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(loads.get(), 2);
+    }
+
+    #[test]
+    fn should_load_again_after_invalidation() {
+        let cache = MemoryEmployeeCache::default();
+        let loads = Cell::new(0);
+        let load = || {
+            loads.set(loads.get() + 1);
+            Ok(vec![])
+        };
+        get_or_load(&cache, &key(false), load).unwrap();
+
+        cache.invalidate().unwrap();
+        get_or_load(&cache, &key(false), load).unwrap();
+
+        assert_eq!(loads.get(), 2);
+    }
+
+    #[test]
+    fn should_not_serve_rows_loaded_before_a_concurrent_invalidation() {
+        let cache = MemoryEmployeeCache::default();
+        let stale = vec![test_employee("ABC123", 1706745600)];
+
+        // The table changes after the rows were read but before they're cached.
+        get_or_load(&cache, &key(false), || {
+            cache.invalidate().unwrap();
+            Ok(stale.clone())
+        })
+        .unwrap();
+        let fresh = get_or_load(&cache, &key(false), || Ok(vec![])).unwrap();
+
+        assert_eq!(fresh, vec![]);
+    }
+
+    #[test]
+    fn should_expire_entries_after_ttl() {
+        let cache = MemoryEmployeeCache::with_ttl(Duration::ZERO);
+        cache.set(0, &key(false), &[]).unwrap();
+
+        assert_eq!(cache.get(0, &key(false)).unwrap(), None);
+    }
+
+    #[test]
+    fn should_not_cache_failed_loads() {
+        let cache = MemoryEmployeeCache::default();
+
+        let result = get_or_load(&cache, &key(false), || {
+            Err(DbError::Connection("down".to_string()))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(cache.get(0, &key(false)).unwrap(), None);
+    }
+}
@@ -1,3 +1,6 @@
-pub fn handle_challenge() {
+use super::EventResponse;
+
+pub fn handle_challenge(challenge: String) -> EventResponse {
     println!("Challenge event");
+    EventResponse::Challenge(challenge)
 }
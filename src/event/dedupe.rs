@@ -0,0 +1,98 @@
+use rocket::request::{FromRequest, Outcome, Request};
+use std::collections::{HashSet, VecDeque};
+use std::sync::Mutex;
+
+const RETRY_NUM_HEADER: &str = "X-Slack-Retry-Num";
+const RETRY_REASON_HEADER: &str = "X-Slack-Retry-Reason";
+const DEFAULT_CAPACITY: usize = 1000;
+
+/// Remembers the last handled `event_id`s so redelivered events are only
+/// acknowledged.
+pub struct ProcessedEvents {
+    capacity: usize,
+    seen: Mutex<(HashSet<String>, VecDeque<String>)>,
+}
+
+impl Default for ProcessedEvents {
+    fn default() -> Self {
+        ProcessedEvents::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl ProcessedEvents {
+    pub fn with_capacity(capacity: usize) -> Self {
+        ProcessedEvents {
+            capacity,
+            seen: Mutex::new((HashSet::new(), VecDeque::new())),
+        }
+    }
+
+    pub fn contains(&self, event_id: &str) -> bool {
+        self.seen.lock().unwrap().0.contains(event_id)
+    }
+
+    pub fn insert(&self, event_id: &str) {
+        let (ids, order) = &mut *self.seen.lock().unwrap();
+        if !ids.insert(event_id.to_string()) {
+            return;
+        }
+
+        order.push_back(event_id.to_string());
+        if order.len() > self.capacity {
+            if let Some(oldest) = order.pop_front() {
+                ids.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Retry information Slack attaches to redelivered events.
+#[derive(Debug, PartialEq)]
+pub struct SlackRetry {
+    pub num: Option<u32>,
+    pub reason: Option<String>,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for SlackRetry {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let headers = req.headers();
+
+        Outcome::Success(SlackRetry {
+            num: headers
+                .get_one(RETRY_NUM_HEADER)
+                .and_then(|n| n.parse().ok()),
+            reason: headers.get_one(RETRY_REASON_HEADER).map(String::from),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_processed_events {
+    use super::ProcessedEvents;
+
+    #[test]
+    fn should_remember_inserted_events() {
+        let processed = ProcessedEvents::default();
+
+        processed.insert("Ev01");
+
+        assert!(processed.contains("Ev01"));
+        assert!(!processed.contains("Ev02"));
+    }
+
+    #[test]
+    fn should_forget_oldest_events_past_capacity() {
+        let processed = ProcessedEvents::with_capacity(2);
+
+        for id in ["Ev01", "Ev02", "Ev02", "Ev03"] {
+            processed.insert(id);
+        }
+
+        assert!(!processed.contains("Ev01"));
+        assert!(processed.contains("Ev02"));
+        assert!(processed.contains("Ev03"));
+    }
+}
@@ -1,11 +1,33 @@
 #[cfg(test)]
 mod tests;
 
-use self::{challenge::handle_challenge, team_join::handle_team_join};
-use crate::authenticate::authenticate;
-use rocket::{http::Status, response::status, serde::json::Json};
+use self::{
+    challenge::handle_challenge,
+    dedupe::{ProcessedEvents, SlackRetry},
+    team_join::{handle_team_join, TeamJoinConfig},
+    unknown::handle_unknown_event,
+    user_change::handle_user_change,
+};
+use crate::{
+    authenticate::SignedJson,
+    cache::SharedEmployeeCache,
+    pg_database::{
+        pool::{checkout, DbConn, DbPool},
+        DbError,
+    },
+    scheduler::store::SharedJobStore,
+    slack_api::SharedSlackApi,
+};
+use rocket::{
+    http::Status,
+    request::{FromRequest, Outcome, Request},
+    State,
+};
 mod challenge;
-mod team_join;
+pub mod dedupe;
+pub mod team_join;
+mod unknown;
+mod user_change;
 
 use serde::{Deserialize, Serialize};
 
@@ -13,62 +35,180 @@ use serde::{Deserialize, Serialize};
 #[serde(tag = "type")]
 pub enum SlackCallback {
     #[serde(rename = "url_verification")]
-    Challenge { token: String, challenge: String },
+    Challenge { challenge: String },
     #[serde(rename = "event_callback")]
-    EventCallback {
-        token: String,
-        api_app_id: String,
-        event: Event,
-    },
+    EventCallback { event_id: String, event: Event },
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(tag = "type")]
 pub enum Event {
     #[serde(rename = "team_join")]
-    TeamJoin { user: TeamJoinUser },
+    TeamJoin { user: SlackUser },
+    #[serde(rename = "user_change")]
+    UserChange { user: SlackUser },
+    /// Any other subscribed event, kept raw so it can be logged and acked.
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
 }
 
+/// Slack leaves the timezone and the email out for bots, and for deleted or
+/// restricted users.
 #[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct TeamJoinUser {
+pub struct SlackUser {
     pub id: String,
-    pub profile: TeamJoinUserProfile,
-    pub tz: String,
-    pub tz_label: String,
+    #[serde(default)]
+    pub profile: SlackUserProfile,
+    pub tz: Option<String>,
+    pub tz_label: Option<String>,
+    #[serde(default)]
+    pub deleted: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct TeamJoinUserProfile {
-    pub email: String,
+impl SlackUser {
+    /// Slack has no country field, so it is derived from labels like "Argentina Time".
+    pub fn country(&self) -> Option<String> {
+        self.tz_label
+            .as_ref()
+            .map(|label| label.to_lowercase().replace(" time", ""))
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct SlackUserProfile {
+    pub email: Option<String>,
+    #[serde(default)]
     pub display_name: String,
+    #[serde(default)]
+    pub real_name: String,
 }
 
-#[post("/event", data = "<json_callback>", format = "json")]
-pub fn event_route(json_callback: Json<SlackCallback>) -> status::Custom<Json<SlackCallback>> {
-    println!("{:?}", json_callback);
+impl SlackUserProfile {
+    /// Slack leaves `display_name` empty for people who never set one.
+    pub fn name(&self) -> Option<String> {
+        [&self.display_name, &self.real_name]
+            .into_iter()
+            .find(|name| !name.trim().is_empty())
+            .cloned()
+    }
+}
+
+/// What `event_route` answers for each `SlackCallback` variant.
+#[derive(Responder, Debug, PartialEq)]
+pub enum EventResponse {
+    /// `url_verification` expects the challenge back as plain text.
+    #[response(status = 200, content_type = "text")]
+    Challenge(String),
+    /// Event callbacks only need a 200 so Slack doesn't retry them.
+    #[response(status = 200)]
+    Ack(()),
+    /// No database connection; Slack retries the delivery later.
+    #[response(status = 503)]
+    Unavailable(()),
+}
+
+/// Managed state the event handlers use besides the database.
+pub struct EventServices<'r> {
+    pub slack: &'r SharedSlackApi,
+    pub jobs: &'r SharedJobStore,
+    pub cache: &'r SharedEmployeeCache,
+    pub team_join: &'r TeamJoinConfig,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for EventServices<'r> {
+    type Error = &'static str;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let rocket = req.rocket();
 
-    let cb = json_callback.clone().into_inner();
-
-    match cb {
-        SlackCallback::Challenge { .. } => handle_challenge(),
-        SlackCallback::EventCallback {
-            event,
-            token,
-            api_app_id,
-        } => {
-            if authenticate(&token, &api_app_id).is_ok() {
-                handle_event(event)
-            } else {
-                println!("Authentication failed");
+        match (
+            rocket.state::<SharedSlackApi>(),
+            rocket.state::<SharedJobStore>(),
+            rocket.state::<SharedEmployeeCache>(),
+            rocket.state::<TeamJoinConfig>(),
+        ) {
+            (Some(slack), Some(jobs), Some(cache), Some(team_join)) => {
+                Outcome::Success(EventServices {
+                    slack,
+                    jobs,
+                    cache,
+                    team_join,
+                })
             }
+            _ => Outcome::Error((
+                Status::InternalServerError,
+                "event services are not managed",
+            )),
         }
     }
+}
+
+#[post("/event", data = "<json_callback>", format = "json")]
+pub async fn event_route(
+    pool: &State<DbPool>,
+    processed: &State<ProcessedEvents>,
+    services: EventServices<'_>,
+    retry: SlackRetry,
+    json_callback: SignedJson<SlackCallback>,
+) -> EventResponse {
+    println!("{:?}", json_callback);
 
-    status::Custom(Status::Ok, json_callback)
+    // Nothing before the checkout touches the database, so challenges,
+    // unknown events and retries are acked without waiting on the pool.
+    let (event_id, event) = match json_callback.into_inner() {
+        SlackCallback::Challenge { challenge } => return handle_challenge(challenge),
+        SlackCallback::EventCallback { event_id, event } => (event_id, event),
+    };
+
+    // Unhandled events are acked without touching the database.
+    if let Event::Unknown(raw) = &event {
+        return handle_unknown_event(&event_id, raw);
+    }
+
+    if processed.contains(&event_id) {
+        println!("Event {} already handled, retry {:?}", event_id, retry);
+        return EventResponse::Ack(());
+    }
+
+    let response = match checkout(pool).await {
+        Ok(mut conn) => handle_event(&mut conn, &event_id, event, &services),
+        Err(e) => {
+            println!("Event not handled: {}", e);
+            EventResponse::Unavailable(())
+        }
+    };
+
+    if response == EventResponse::Ack(()) {
+        processed.insert(&event_id);
+    }
+    response
 }
 
-fn handle_event(event: Event) {
-    match event {
-        Event::TeamJoin { user } => handle_team_join(user),
+fn handle_event(
+    conn: &mut DbConn,
+    event_id: &str,
+    event: Event,
+    services: &EventServices,
+) -> EventResponse {
+    let result = match event {
+        Event::TeamJoin { user } => handle_team_join(conn, user, services),
+        Event::UserChange { user } => handle_user_change(conn, user, services.cache),
+        // `event_route` acks these before getting a connection, but they are
+        // still safe to pass here.
+        Event::Unknown(raw) => return handle_unknown_event(event_id, &raw),
+    };
+
+    match result {
+        Ok(()) => EventResponse::Ack(()),
+        // Only worth a retry if the database may come back.
+        Err(e @ DbError::Connection(_)) => {
+            println!("Event not handled: {}", e);
+            EventResponse::Unavailable(())
+        }
+        Err(e) => {
+            println!("Event dropped: {}", e);
+            EventResponse::Ack(())
+        }
     }
 }
@@ -1,15 +1,140 @@
-use super::TeamJoinUser;
-use crate::{models::Employee, pg_database::save_employee};
-use chrono::Local;
+use super::{EventServices, SlackUser};
+use crate::{
+    cache::spawn_invalidate,
+    models::Employee,
+    pg_database::{
+        announcements::get_announcement_settings,
+        buddies::{assign_buddy, find_buddy_candidate, get_buddy},
+        get_employee, save_employee, DbError, SaveMode,
+    },
+    scheduler::{check_in_jobs, spawn_schedule},
+    slack_api::spawn_dms,
+    utils::response_templates::{
+        announcement_template, buddy_assigned_dm_template, welcome_template, your_buddy_dm_template,
+    },
+};
+use chrono::Utc;
+use diesel::pg::PgConnection;
+use std::env;
 
-pub fn handle_team_join(user: TeamJoinUser) {
+const DEFAULT_WELCOME_MESSAGE: &str =
+    "¡Hola {usuario}! Te damos la bienvenida al equipo. Escribí /ayuda para ver en qué te puedo ayudar.";
+
+/// What the bot does for every new employee. `WELCOME_MESSAGE` overrides the
+/// default DM unless empty, and may use `{usuario}` to tag the person. `BUDDY_AUTO_ASSIGN=true`
+/// picks a buddy from the same country.
+pub struct TeamJoinConfig {
+    pub welcome_template: String,
+    pub auto_assign_buddy: bool,
+}
+
+impl TeamJoinConfig {
+    pub fn from_env() -> Self {
+        TeamJoinConfig {
+            welcome_template: env::var("WELCOME_MESSAGE")
+                .ok()
+                .filter(|message| !message.trim().is_empty())
+                .unwrap_or(DEFAULT_WELCOME_MESSAGE.to_string()),
+            auto_assign_buddy: env::var("BUDDY_AUTO_ASSIGN")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+        }
+    }
+}
+
+pub fn handle_team_join(
+    conn: &mut PgConnection,
+    user: SlackUser,
+    services: &EventServices,
+) -> Result<(), DbError> {
+    let config = services.team_join;
     let employee = Employee {
+        country: user.country(),
         id: user.id,
-        email: user.profile.email,
-        full_name: user.profile.display_name,
-        country: Some(user.tz_label.to_lowercase().replace(" time", "")),
-        join_date: Local::now().naive_utc(),
+        full_name: user.profile.name().unwrap_or_default(),
+        email: user.profile.email.filter(|email| !email.is_empty()),
+        join_date: Utc::now(),
+        active: true,
+        left_date: None,
+    };
+
+    // Redeliveries and rejoins find the employee stored, and their check-ins
+    // already scheduled or sent.
+    let is_new = match get_employee(conn, &employee.id) {
+        Ok(_) => false,
+        Err(DbError::NotFound) => true,
+        Err(e) => return Err(e),
+    };
+
+    // Upsert so a redelivered team_join doesn't trip the primary key.
+    let employee = save_employee(conn, &employee, SaveMode::Upsert)?;
+    spawn_invalidate(services.cache);
+
+    // A broken announcement setup shouldn't make Slack redeliver the event.
+    let announcement = match get_announcement_settings(conn) {
+        Ok(settings) if settings.enabled => settings.channel_id.clone().map(|channel| {
+            let text = announcement_template(&settings, &employee.id, employee.country.as_deref());
+            (channel, text)
+        }),
+        Ok(_) => None,
+        Err(e) => {
+            println!("Announcement settings not loaded: {}", e);
+            None
+        }
+    };
+
+    let mut dms = vec![(
+        employee.id.clone(),
+        welcome_template(&config.welcome_template, &employee.id),
+    )];
+    if config.auto_assign_buddy {
+        match auto_assign_buddy(conn, &employee) {
+            Ok(Some(buddy_id)) => {
+                dms.push((employee.id.clone(), your_buddy_dm_template(&buddy_id)));
+                dms.push((buddy_id, buddy_assigned_dm_template(&employee.id)));
+            }
+            Ok(None) => {}
+            Err(e) => println!("Buddy for {} not assigned: {}", employee.id, e),
+        }
+    }
+
+    // Scheduled and sent in the background so Slack gets its ack within the
+    // 3 seconds.
+    if is_new {
+        spawn_schedule(services.jobs, check_in_jobs(&employee));
+    }
+    spawn_dms(services.slack, dms);
+    if let Some((channel, text)) = announcement {
+        let slack = services.slack.clone();
+        rocket::tokio::spawn(async move {
+            if let Err(e) = slack.post_message(&channel, &text).await {
+                println!("Announcement of {} not sent: {}", employee.id, e);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns the newly assigned buddy, if any. People who already have one, like
+/// someone rejoining, keep it.
+fn auto_assign_buddy(
+    conn: &mut PgConnection,
+    employee: &Employee,
+) -> Result<Option<String>, DbError> {
+    let country = match &employee.country {
+        Some(country) => country,
+        None => return Ok(None),
     };
+    if get_buddy(conn, &employee.id)?.is_some() {
+        return Ok(None);
+    }
 
-    save_employee(&employee);
+    match find_buddy_candidate(conn, country, &employee.id, Utc::now())? {
+        Some(candidate) => {
+            assign_buddy(conn, &employee.id, &candidate.id, Utc::now())?;
+            Ok(Some(candidate.id))
+        }
+        None => Ok(None),
+    }
 }
@@ -1,7 +1,426 @@
 #[cfg(test)]
 mod test_database_actions {
+    use std::sync::Arc;
+
+    use chrono::{NaiveDate, Utc};
+
+    use crate::cache::{MemoryEmployeeCache, SharedEmployeeCache};
+    use crate::event::{
+        team_join::{handle_team_join, TeamJoinConfig},
+        user_change::handle_user_change,
+        EventServices, SlackUser, SlackUserProfile,
+    };
+    use crate::models::test_employee;
+    use crate::pg_database::{
+        get_employee, mark_employee_left, save_employee, test_connection, LeftBy, SaveMode,
+    };
+    use crate::scheduler::store::{JobStore, MemoryJobStore, SharedJobStore};
+    use crate::slack_api::{ChannelSlackApi, SharedSlackApi};
+    use rocket::tokio::time::sleep;
+    use std::time::Duration;
+
     #[test]
     fn init() {
         assert_eq!(1, 1);
     }
+
+    fn slack_user(deleted: bool) -> SlackUser {
+        SlackUser {
+            id: "UBAJA1".to_string(),
+            profile: SlackUserProfile {
+                email: Some("baja@example.com".to_string()),
+                display_name: "nuevo nombre".to_string(),
+                ..Default::default()
+            },
+            tz: Some("America/Argentina/Buenos_Aires".to_string()),
+            tz_label: Some("Argentina Time".to_string()),
+            deleted,
+        }
+    }
+
+    fn cache() -> SharedEmployeeCache {
+        Arc::new(MemoryEmployeeCache::default())
+    }
+
+    #[rocket::async_test]
+    async fn should_keep_offboardings_after_profile_changes() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        let left_date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        save_employee(
+            &mut conn,
+            &test_employee("UBAJA1", 1704067200),
+            SaveMode::Upsert,
+        )
+        .unwrap();
+        mark_employee_left(&mut conn, "UBAJA1", left_date, LeftBy::Admin).unwrap();
+
+        handle_user_change(&mut conn, slack_user(false), &cache()).unwrap();
+
+        let employee = get_employee(&mut conn, "UBAJA1").unwrap();
+        assert_eq!(employee.full_name, "nuevo nombre");
+        assert!(!employee.active);
+        assert_eq!(employee.left_date, Some(left_date));
+    }
+
+    #[rocket::async_test]
+    async fn should_reactivate_users_slack_restores() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        save_employee(
+            &mut conn,
+            &test_employee("UBAJA1", 1704067200),
+            SaveMode::Upsert,
+        )
+        .unwrap();
+
+        handle_user_change(&mut conn, slack_user(true), &cache()).unwrap();
+        assert!(!get_employee(&mut conn, "UBAJA1").unwrap().active);
+        handle_user_change(&mut conn, slack_user(false), &cache()).unwrap();
+
+        let employee = get_employee(&mut conn, "UBAJA1").unwrap();
+        assert!(employee.active);
+        assert_eq!(employee.left_date, None);
+    }
+
+    #[rocket::async_test]
+    async fn should_let_admins_offboard_users_slack_deleted() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        let left_date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        save_employee(
+            &mut conn,
+            &test_employee("UBAJA1", 1704067200),
+            SaveMode::Upsert,
+        )
+        .unwrap();
+
+        handle_user_change(&mut conn, slack_user(true), &cache()).unwrap();
+        let employee = mark_employee_left(&mut conn, "UBAJA1", left_date, LeftBy::Admin).unwrap();
+        assert_eq!(employee.left_date, Some(left_date));
+        handle_user_change(&mut conn, slack_user(false), &cache()).unwrap();
+
+        let employee = get_employee(&mut conn, "UBAJA1").unwrap();
+        assert!(!employee.active);
+        assert_eq!(employee.left_date, Some(left_date));
+    }
+
+    #[rocket::async_test]
+    async fn should_use_real_name_when_display_name_is_empty() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        save_employee(
+            &mut conn,
+            &test_employee("UBAJA1", 1704067200),
+            SaveMode::Upsert,
+        )
+        .unwrap();
+        let mut user = slack_user(false);
+        user.profile.display_name = String::new();
+        user.profile.real_name = "Nombre Real".to_string();
+
+        handle_user_change(&mut conn, user, &cache()).unwrap();
+
+        assert_eq!(
+            get_employee(&mut conn, "UBAJA1").unwrap().full_name,
+            "Nombre Real"
+        );
+    }
+
+    #[rocket::async_test]
+    async fn should_keep_full_name_of_deleted_users_without_profile() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        save_employee(
+            &mut conn,
+            &test_employee("UBAJA1", 1704067200),
+            SaveMode::Upsert,
+        )
+        .unwrap();
+        let user = SlackUser {
+            id: "UBAJA1".to_string(),
+            profile: SlackUserProfile::default(),
+            tz: None,
+            tz_label: None,
+            deleted: true,
+        };
+
+        handle_user_change(&mut conn, user, &cache()).unwrap();
+
+        let employee = get_employee(&mut conn, "UBAJA1").unwrap();
+        assert_eq!(
+            employee.full_name,
+            test_employee("UBAJA1", 1704067200).full_name
+        );
+        assert!(!employee.active);
+    }
+
+    #[rocket::async_test]
+    async fn should_schedule_check_ins_only_for_new_employees() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        let slack: SharedSlackApi = Arc::new(ChannelSlackApi::new(None).0);
+        let store = Arc::new(MemoryJobStore::default());
+        let jobs: SharedJobStore = store.clone();
+        let config = TeamJoinConfig {
+            welcome_template: "Hola {usuario}".to_string(),
+            auto_assign_buddy: false,
+        };
+        let services = EventServices {
+            slack: &slack,
+            jobs: &jobs,
+            cache: &cache(),
+            team_join: &config,
+        };
+
+        handle_team_join(&mut conn, slack_user(false), &services).unwrap();
+        for _ in 0..20 {
+            if store.jobs().len() == 3 {
+                break;
+            }
+            sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(store.jobs().len(), 3);
+        // As if all of them had already run.
+        let after_day_30 = (Utc::now() + chrono::Duration::days(31)).timestamp();
+        for job in store.claim_due(after_day_30, 3).unwrap() {
+            store.complete(&job).unwrap();
+        }
+
+        handle_team_join(&mut conn, slack_user(false), &services).unwrap();
+        sleep(Duration::from_millis(200)).await;
+
+        assert_eq!(store.jobs(), vec![]);
+    }
+
+    #[rocket::async_test]
+    async fn should_save_everyone_who_joins_without_email() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        let slack: SharedSlackApi = Arc::new(ChannelSlackApi::new(None).0);
+        let jobs: SharedJobStore = Arc::new(MemoryJobStore::default());
+        let config = TeamJoinConfig {
+            welcome_template: "Hola {usuario}".to_string(),
+            auto_assign_buddy: false,
+        };
+        let services = EventServices {
+            slack: &slack,
+            jobs: &jobs,
+            cache: &cache(),
+            team_join: &config,
+        };
+
+        for id in ["USINMAIL1", "USINMAIL2"] {
+            let user = SlackUser {
+                id: id.to_string(),
+                profile: SlackUserProfile::default(),
+                tz: None,
+                tz_label: None,
+                deleted: false,
+            };
+            handle_team_join(&mut conn, user, &services).unwrap();
+        }
+
+        for id in ["USINMAIL1", "USINMAIL2"] {
+            assert_eq!(get_employee(&mut conn, id).unwrap().email, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_event_route {
+    use chrono::Utc;
+    use rocket::{
+        http::{ContentType, Header, Status},
+        local::blocking::{Client, LocalResponse},
+    };
+
+    use crate::authenticate::{sign, SigningSecret, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+    use crate::cache::{MemoryEmployeeCache, SharedEmployeeCache};
+    use crate::event::{dedupe::ProcessedEvents, event_route, team_join::TeamJoinConfig};
+    use crate::pg_database::pool::unavailable_pool;
+    use crate::scheduler::store::{MemoryJobStore, SharedJobStore};
+    use crate::slack_api::{SharedSlackApi, SlackClient};
+    use std::sync::Arc;
+
+    const SECRET: &str = "test_signing_secret";
+    const CHALLENGE_BODY: &str = r#"{"token":"Jhj5dZrVaK7ZwHHjRyZWjbDl","challenge":"3eZbrw1aBm2rZgRNFdxV2595E9CY3gmdALWMmHkvFXO7tYXAYM8P","type":"url_verification"}"#;
+    const TEAM_JOIN_BODY: &str = r#"{"type":"event_callback","event_id":"Ev01","event":{"type":"team_join","user":{"id":"U123","tz":"America/Argentina/Buenos_Aires","tz_label":"Argentina Time","profile":{"email":"new@example.com","display_name":"new"}}}}"#;
+
+    fn client() -> Client {
+        let rocket = rocket::build()
+            .manage(SigningSecret(SECRET.to_string()))
+            .manage(unavailable_pool())
+            .manage(ProcessedEvents::default())
+            .manage(Arc::new(SlackClient::new("xoxb-test", "http://127.0.0.1:9")) as SharedSlackApi)
+            .manage(Arc::new(MemoryEmployeeCache::default()) as SharedEmployeeCache)
+            .manage(Arc::new(MemoryJobStore::default()) as SharedJobStore)
+            .manage(TeamJoinConfig {
+                welcome_template: "Hola {usuario}".to_string(),
+                auto_assign_buddy: false,
+            })
+            .mount("/", routes![event_route]);
+        Client::tracked(rocket).unwrap()
+    }
+
+    fn post_event<'c>(
+        client: &'c Client,
+        secret: &str,
+        body: &'static str,
+        headers: Vec<Header<'static>>,
+    ) -> LocalResponse<'c> {
+        let ts = Utc::now().timestamp().to_string();
+        let mut req = client
+            .post("/event")
+            .header(ContentType::JSON)
+            .header(Header::new(TIMESTAMP_HEADER, ts.clone()))
+            .header(Header::new(SIGNATURE_HEADER, sign(secret, &ts, body)))
+            .body(body);
+        for header in headers {
+            req = req.header(header);
+        }
+        req.dispatch()
+    }
+
+    #[test]
+    fn should_answer_challenge_with_plain_challenge_value() {
+        let client = client();
+
+        let res = post_event(&client, SECRET, CHALLENGE_BODY, vec![]);
+
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!(res.content_type(), Some(ContentType::Text));
+        assert_eq!(
+            res.into_string().unwrap(),
+            "3eZbrw1aBm2rZgRNFdxV2595E9CY3gmdALWMmHkvFXO7tYXAYM8P"
+        );
+    }
+
+    #[test]
+    fn should_reject_unsigned_challenge() {
+        let client = client();
+
+        let res = post_event(&client, "wrong", CHALLENGE_BODY, vec![]);
+
+        assert_eq!(res.status(), Status::Unauthorized);
+    }
+
+    #[test]
+    fn should_ask_for_a_retry_when_database_is_unavailable() {
+        let client = client();
+
+        let res = post_event(&client, SECRET, TEAM_JOIN_BODY, vec![]);
+
+        assert_eq!(res.status(), Status::ServiceUnavailable);
+    }
+
+    #[test]
+    fn should_ack_unknown_events_without_database() {
+        let client = client();
+        let body = r#"{"type":"event_callback","event_id":"Ev03","event":{"type":"reaction_added","user":"U123","reaction":"tada","item":{"type":"message","channel":"C123","ts":"1360782400.498405"}}}"#;
+
+        let res = post_event(&client, SECRET, body, vec![]);
+
+        assert_eq!(res.status(), Status::Ok);
+    }
+
+    #[test]
+    fn should_ack_retries_of_handled_events_without_side_effects() {
+        let client = client();
+        client
+            .rocket()
+            .state::<ProcessedEvents>()
+            .unwrap()
+            .insert("Ev01");
+
+        // The database is unreachable, so any side effect would answer 503.
+        let res = post_event(
+            &client,
+            SECRET,
+            TEAM_JOIN_BODY,
+            vec![
+                Header::new("X-Slack-Retry-Num", "1"),
+                Header::new("X-Slack-Retry-Reason", "http_timeout"),
+            ],
+        );
+
+        assert_eq!(res.status(), Status::Ok);
+    }
+}
+
+#[cfg(test)]
+mod test_slack_event {
+    use crate::event::{Event, SlackCallback};
+
+    #[test]
+    fn should_parse_user_change_with_deactivation() {
+        let body = r#"{"type":"event_callback","event_id":"Ev02","event":{"type":"user_change","user":{"id":"U123","deleted":true,"tz":"America/Santiago","tz_label":"Chile Time","profile":{"email":"old@example.com","display_name":"old"}}}}"#;
+
+        let cb: SlackCallback = serde_json::from_str(body).unwrap();
+
+        match cb {
+            SlackCallback::EventCallback {
+                event: Event::UserChange { user },
+                ..
+            } => {
+                assert!(user.deleted);
+                assert_eq!(user.country(), Some("chile".to_string()));
+            }
+            other => panic!("unexpected callback {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_parse_deleted_users_without_timezone_or_email() {
+        let body = r#"{"type":"event_callback","event_id":"Ev03","event":{"type":"user_change","user":{"id":"U123","team_id":"T1","name":"old","deleted":true,"profile":{"title":"","phone":"","skype":"","real_name":"Old","real_name_normalized":"Old","display_name":"old","display_name_normalized":"old","fields":null,"status_text":"","status_emoji":"","status_expiration":0,"avatar_hash":"g1a2b3c4d5e6","first_name":"Old","last_name":"","image_24":"https://secure.gravatar.com/avatar/1.jpg","image_512":"https://secure.gravatar.com/avatar/1.jpg","status_text_canonical":"","team":"T1"},"is_bot":false,"is_app_user":false,"updated":1700000000,"is_email_confirmed":true,"who_can_share_contact_card":"EVERYONE"},"cache_ts":1700000000,"event_ts":"1700000000.000100"}}"#;
+
+        let cb: SlackCallback = serde_json::from_str(body).unwrap();
+
+        match cb {
+            SlackCallback::EventCallback {
+                event: Event::UserChange { user },
+                ..
+            } => {
+                assert!(user.deleted);
+                assert_eq!(user.profile.email, None);
+                assert_eq!(user.profile.name(), Some("old".to_string()));
+                assert_eq!(user.country(), None);
+            }
+            other => panic!("unexpected callback {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_keep_raw_payload_of_unknown_events() {
+        let body = r#"{"type":"app_mention","user":"U123","text":"hola"}"#;
+
+        let event: Event = serde_json::from_str(body).unwrap();
+
+        match event {
+            Event::Unknown(raw) => {
+                assert_eq!(raw["type"], "app_mention");
+                assert_eq!(raw["text"], "hola");
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_default_deleted_to_false() {
+        let body = r#"{"type":"team_join","user":{"id":"U123","tz":"America/Argentina/Buenos_Aires","tz_label":"Argentina Time","profile":{"email":"new@example.com","display_name":"new"}}}"#;
+
+        let event: Event = serde_json::from_str(body).unwrap();
+
+        match event {
+            Event::TeamJoin { user } => assert!(!user.deleted),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,24 @@
+use super::EventResponse;
+use serde_json::Value;
+
+const KNOWN_EVENT_TYPES: [&str; 2] = ["team_join", "user_change"];
+
+/// Acknowledges events the bot doesn't handle, so Slack stops retrying them.
+pub fn handle_unknown_event(event_id: &str, raw: &Value) -> EventResponse {
+    let event_type = raw.get("type").and_then(Value::as_str).unwrap_or("<none>");
+
+    if KNOWN_EVENT_TYPES.contains(&event_type) {
+        // A handled type only lands here if its payload didn't match.
+        warn!(
+            "slack_event status=malformed event_type={} event_id={} payload={}",
+            event_type, event_id, raw
+        );
+    } else {
+        info!(
+            "slack_event status=ignored event_type={} event_id={}",
+            event_type, event_id
+        );
+    }
+
+    EventResponse::Ack(())
+}
@@ -0,0 +1,36 @@
+use super::SlackUser;
+use crate::{
+    cache::{spawn_invalidate, SharedEmployeeCache},
+    models::EmployeeProfile,
+    pg_database::{
+        mark_employee_active, mark_employee_left, update_employee_profile, DbError, LeftBy,
+    },
+};
+use chrono::Utc;
+use diesel::{pg::PgConnection, Connection};
+
+pub fn handle_user_change(
+    conn: &mut PgConnection,
+    user: SlackUser,
+    cache: &SharedEmployeeCache,
+) -> Result<(), DbError> {
+    let profile = EmployeeProfile {
+        email: user.profile.email.clone(),
+        full_name: user.profile.name(),
+        country: user.country(),
+    };
+
+    conn.transaction::<_, DbError, _>(|conn| {
+        update_employee_profile(conn, &user.id, &profile)?;
+
+        if user.deleted {
+            mark_employee_left(conn, &user.id, Utc::now().date_naive(), LeftBy::Slack)?;
+        } else {
+            mark_employee_active(conn, &user.id)?;
+        }
+        Ok(())
+    })?;
+
+    spawn_invalidate(cache);
+    Ok(())
+}
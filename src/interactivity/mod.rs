@@ -0,0 +1,182 @@
+#[cfg(test)]
+mod tests;
+
+mod nuevos_page;
+pub mod nuevos_range;
+pub mod registry;
+
+use self::{
+    nuevos_page::handle_nuevos_page,
+    nuevos_range::handle_nuevos_range,
+    registry::{InteractionKind, InteractionRegistry},
+};
+use crate::{
+    authenticate::SignedForm,
+    cache::SharedEmployeeCache,
+    pg_database::pool::DbPool,
+    slack_api::SharedSlackApi,
+    slash_command::nuevos::{PAGE_ACTION, RANGE_CALLBACK},
+};
+use rocket::{serde::json::Json, State};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Slack posts interactions as a form with the JSON in `payload`.
+#[derive(FromForm, Debug)]
+pub struct InteractionForm {
+    pub payload: String,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum Interaction {
+    /// Buttons and other elements in messages or views.
+    #[serde(rename = "block_actions")]
+    BlockActions {
+        user: InteractionUser,
+        trigger_id: String,
+        /// Only set for actions on messages.
+        response_url: Option<String>,
+        actions: Vec<BlockAction>,
+    },
+    #[serde(rename = "view_submission")]
+    ViewSubmission { user: InteractionUser, view: View },
+    /// Global shortcuts, from the shortcuts menu.
+    #[serde(rename = "shortcut")]
+    Shortcut {
+        user: InteractionUser,
+        trigger_id: String,
+        callback_id: String,
+    },
+    /// Interactions the bot doesn't offer, like message shortcuts.
+    #[serde(other)]
+    Unknown,
+}
+
+impl Interaction {
+    /// What the handler is registered by, if the bot handles this kind.
+    pub fn handler_key(&self) -> Option<(InteractionKind, &str)> {
+        match self {
+            Interaction::BlockActions { actions, .. } => actions
+                .first()
+                .map(|action| (InteractionKind::BlockActions, action.action_id.as_str())),
+            Interaction::ViewSubmission { view, .. } => {
+                Some((InteractionKind::ViewSubmission, &view.callback_id))
+            }
+            Interaction::Shortcut { callback_id, .. } => {
+                Some((InteractionKind::Shortcut, callback_id))
+            }
+            Interaction::Unknown => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct InteractionUser {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct BlockAction {
+    pub action_id: String,
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct View {
+    pub id: String,
+    pub callback_id: String,
+    /// Whatever the bot stored when it opened the view.
+    #[serde(default)]
+    pub private_metadata: String,
+    pub state: ViewState,
+}
+
+/// Input values by block id and then by action id, as Slack nests them.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ViewState {
+    pub values: HashMap<String, HashMap<String, Value>>,
+}
+
+impl ViewState {
+    /// The input of `action_id` in `block_id`, e.g. `{"type":"datepicker","selected_date":"2024-01-01"}`.
+    pub fn value(&self, block_id: &str, action_id: &str) -> Option<&Value> {
+        self.values.get(block_id)?.get(action_id)
+    }
+}
+
+/// Managed state the interaction handlers use.
+pub struct InteractionContext {
+    /// Handlers check out connections in their background tasks, after the ack.
+    pub pool: DbPool,
+    pub cache: SharedEmployeeCache,
+    pub slack: SharedSlackApi,
+}
+
+#[derive(Responder, Debug, PartialEq)]
+pub enum InteractionResponse {
+    /// Empty 200: Slack closes modals and keeps messages as they are.
+    #[response(status = 200)]
+    Ack(()),
+    /// Only valid as the answer to a `view_submission`.
+    #[response(status = 200)]
+    Action(Json<ResponseAction>),
+    #[response(status = 400)]
+    Invalid(()),
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(tag = "response_action")]
+pub enum ResponseAction {
+    /// Keeps the modal open with a message under each of these blocks.
+    #[serde(rename = "errors")]
+    Errors { errors: HashMap<String, String> },
+}
+
+/// The handlers of every button, modal and shortcut the bot offers.
+pub fn interaction_registry() -> InteractionRegistry {
+    InteractionRegistry::default()
+        .register(
+            InteractionKind::BlockActions,
+            PAGE_ACTION,
+            handle_nuevos_page,
+        )
+        .register(
+            InteractionKind::ViewSubmission,
+            RANGE_CALLBACK,
+            handle_nuevos_range,
+        )
+}
+
+#[post(
+    "/interactivity",
+    data = "<form>",
+    format = "application/x-www-form-urlencoded"
+)]
+pub fn interactivity_route(
+    pool: &State<DbPool>,
+    cache: &State<SharedEmployeeCache>,
+    slack: &State<SharedSlackApi>,
+    registry: &State<InteractionRegistry>,
+    form: SignedForm<InteractionForm>,
+) -> InteractionResponse {
+    let interaction = match serde_json::from_str::<Interaction>(&form.into_inner().payload) {
+        Ok(interaction) => interaction,
+        Err(e) => {
+            println!("Interaction not understood: {}", e);
+            return InteractionResponse::Invalid(());
+        }
+    };
+    // Payloads carry response URLs and modal state, so only what routes them
+    // is logged.
+    println!("Interaction: {:?}", interaction.handler_key());
+
+    let context = InteractionContext {
+        pool: pool.inner().clone(),
+        cache: cache.inner().clone(),
+        slack: slack.inner().clone(),
+    };
+    registry.dispatch(context, interaction)
+}
@@ -0,0 +1,36 @@
+use super::{Interaction, InteractionContext, InteractionResponse};
+use crate::slash_command::nuevos::{spawn_new_employees_response, NuevosQuery};
+
+/// "Anterior"/"Siguiente" on a `/nuevos` answer: the button value is the page
+/// to show, which replaces the message.
+pub fn handle_nuevos_page(
+    context: InteractionContext,
+    interaction: Interaction,
+) -> InteractionResponse {
+    let Interaction::BlockActions {
+        response_url: Some(response_url),
+        actions,
+        ..
+    } = interaction
+    else {
+        return InteractionResponse::Ack(());
+    };
+
+    let query = actions
+        .first()
+        .and_then(|action| action.value.as_deref())
+        .and_then(|value| serde_json::from_str::<NuevosQuery>(value).ok());
+
+    match query {
+        Some(query) => spawn_new_employees_response(
+            context.pool,
+            context.cache,
+            context.slack,
+            response_url,
+            query,
+            true,
+        ),
+        None => println!("Invalid /nuevos page: {:?}", actions),
+    }
+    InteractionResponse::Ack(())
+}
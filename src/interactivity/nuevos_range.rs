@@ -0,0 +1,101 @@
+use super::{Interaction, InteractionContext, InteractionResponse, ResponseAction, View};
+use crate::slash_command::nuevos::{
+    spawn_new_employees_response, NuevosQuery, RangeModalMetadata, COUNTRY_BLOCK, FROM_BLOCK,
+    INPUT_ACTION, TO_BLOCK,
+};
+
+use crate::utils::{parse_interval::local_to_utc, DateRound};
+
+use chrono::NaiveDate;
+use rocket::serde::json::Json;
+use std::collections::HashMap;
+
+/// The query of a submitted date range modal, or the errors to show in it.
+pub fn parse_range_submission(
+    view: &View,
+) -> Result<(NuevosQuery, RangeModalMetadata), HashMap<String, String>> {
+    let metadata =
+        serde_json::from_str::<RangeModalMetadata>(&view.private_metadata).map_err(|e| {
+            println!("Invalid /nuevos modal metadata: {}", e);
+            HashMap::from([(
+                FROM_BLOCK.to_string(),
+                "Se perdió el comando original. Volvé a escribir /nuevos.".to_string(),
+            )])
+        })?;
+
+    let [from, to] = [FROM_BLOCK, TO_BLOCK].map(|block_id| {
+        view.state
+            .value(block_id, INPUT_ACTION)
+            .and_then(|value| value["selected_date"].as_str())
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+    });
+    let (from, to) = match (from, to) {
+        (Some(from), Some(to)) if from <= to => (from, to),
+        (Some(_), Some(_)) => {
+            return Err(HashMap::from([(
+                TO_BLOCK.to_string(),
+                "Tiene que ser igual o posterior a la fecha de inicio.".to_string(),
+            )]))
+        }
+        (from, _) => {
+            let block_id = if from.is_none() { FROM_BLOCK } else { TO_BLOCK };
+            return Err(HashMap::from([(
+                block_id.to_string(),
+                "Elegí una fecha.".to_string(),
+            )]));
+        }
+    };
+
+    let countries = view
+        .state
+        .value(COUNTRY_BLOCK, INPUT_ACTION)
+        .and_then(|value| value["value"].as_str())
+        .unwrap_or_default()
+        .split(',')
+        .map(|country| country.trim().to_lowercase())
+        .filter(|country| !country.is_empty())
+        .collect();
+
+    let [from, to] = [
+        (from.and_hms_opt(0, 0, 0).unwrap(), DateRound::Floor),
+        (to.and_hms_opt(23, 59, 59).unwrap(), DateRound::Ceil),
+    ]
+    .map(|(date, round)| local_to_utc(date, metadata.tz, round).timestamp());
+    let query = NuevosQuery {
+        from: Some(from),
+        to,
+        include_inactive: metadata.include_inactive,
+        in_channel: metadata.in_channel,
+        countries,
+        group_by_country: metadata.group_by_country,
+        tz: metadata.tz,
+        page: 0,
+    };
+    Ok((query, metadata))
+}
+
+/// Submission of the `/nuevos` modal: closes it and posts the list where the
+/// command was written.
+pub fn handle_nuevos_range(
+    context: InteractionContext,
+    interaction: Interaction,
+) -> InteractionResponse {
+    let Interaction::ViewSubmission { view, .. } = interaction else {
+        return InteractionResponse::Ack(());
+    };
+
+    match parse_range_submission(&view) {
+        Ok((query, metadata)) => {
+            spawn_new_employees_response(
+                context.pool,
+                context.cache,
+                context.slack,
+                metadata.response_url,
+                query,
+                false,
+            );
+            InteractionResponse::Ack(())
+        }
+        Err(errors) => InteractionResponse::Action(Json(ResponseAction::Errors { errors })),
+    }
+}
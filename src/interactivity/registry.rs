@@ -0,0 +1,50 @@
+use super::{Interaction, InteractionContext, InteractionResponse};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InteractionKind {
+    BlockActions,
+    ViewSubmission,
+    Shortcut,
+}
+
+/// Handlers get the whole interaction, already matched by kind and id.
+pub type InteractionHandler = fn(InteractionContext, Interaction) -> InteractionResponse;
+
+/// Handlers by kind and `action_id` (block actions) or `callback_id` (views
+/// and shortcuts).
+#[derive(Default)]
+pub struct InteractionRegistry {
+    handlers: HashMap<(InteractionKind, String), InteractionHandler>,
+}
+
+impl InteractionRegistry {
+    pub fn register(
+        mut self,
+        kind: InteractionKind,
+        id: &str,
+        handler: InteractionHandler,
+    ) -> Self {
+        self.handlers.insert((kind, id.to_string()), handler);
+        self
+    }
+
+    /// Interactions nobody registered for are logged and acked, so Slack
+    /// doesn't show the user an error.
+    pub fn dispatch(
+        &self,
+        context: InteractionContext,
+        interaction: Interaction,
+    ) -> InteractionResponse {
+        let key = interaction.handler_key();
+        let handler = key.and_then(|(kind, id)| self.handlers.get(&(kind, id.to_string())));
+
+        match handler {
+            Some(handler) => handler(context, interaction),
+            None => {
+                println!("Unhandled interaction: {:?}", key);
+                InteractionResponse::Ack(())
+            }
+        }
+    }
+}
@@ -0,0 +1,400 @@
+#[cfg(test)]
+mod test_interaction {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use crate::interactivity::{
+        registry::InteractionKind, BlockAction, Interaction, InteractionUser, View, ViewState,
+    };
+
+    fn user() -> InteractionUser {
+        InteractionUser {
+            id: "U123".to_string(),
+        }
+    }
+
+    #[test]
+    fn should_parse_block_actions() {
+        let payload = r#"{"type":"block_actions","user":{"id":"U123"},"trigger_id":"1.2.abc","response_url":"https://hooks.slack.com/actions/T1/2/3","actions":[{"type":"button","action_id":"nuevos_page","value":"1"}]}"#;
+
+        let interaction = serde_json::from_str::<Interaction>(payload).unwrap();
+
+        assert_eq!(
+            interaction,
+            Interaction::BlockActions {
+                user: user(),
+                trigger_id: "1.2.abc".to_string(),
+                response_url: Some("https://hooks.slack.com/actions/T1/2/3".to_string()),
+                actions: vec![BlockAction {
+                    action_id: "nuevos_page".to_string(),
+                    value: Some("1".to_string()),
+                }],
+            }
+        );
+        assert_eq!(
+            interaction.handler_key(),
+            Some((InteractionKind::BlockActions, "nuevos_page"))
+        );
+    }
+
+    #[test]
+    fn should_parse_view_submissions() {
+        let payload = r#"{"type":"view_submission","user":{"id":"U123"},"view":{"id":"V1","callback_id":"nuevos_rango","private_metadata":"C1","state":{"values":{"desde":{"fecha":{"type":"datepicker","selected_date":"2024-01-01"}}}}}}"#;
+
+        let interaction = serde_json::from_str::<Interaction>(payload).unwrap();
+
+        assert_eq!(
+            interaction,
+            Interaction::ViewSubmission {
+                user: user(),
+                view: View {
+                    id: "V1".to_string(),
+                    callback_id: "nuevos_rango".to_string(),
+                    private_metadata: "C1".to_string(),
+                    state: ViewState {
+                        values: HashMap::from([(
+                            "desde".to_string(),
+                            HashMap::from([(
+                                "fecha".to_string(),
+                                json!({ "type": "datepicker", "selected_date": "2024-01-01" })
+                            )])
+                        )]),
+                    },
+                },
+            }
+        );
+        assert_eq!(
+            interaction.handler_key(),
+            Some((InteractionKind::ViewSubmission, "nuevos_rango"))
+        );
+    }
+
+    #[test]
+    fn should_parse_shortcuts() {
+        let payload = r#"{"type":"shortcut","user":{"id":"U123"},"trigger_id":"1.2.abc","callback_id":"nuevos"}"#;
+
+        let interaction = serde_json::from_str::<Interaction>(payload).unwrap();
+
+        assert_eq!(
+            interaction,
+            Interaction::Shortcut {
+                user: user(),
+                trigger_id: "1.2.abc".to_string(),
+                callback_id: "nuevos".to_string(),
+            }
+        );
+        assert_eq!(
+            interaction.handler_key(),
+            Some((InteractionKind::Shortcut, "nuevos"))
+        );
+    }
+
+    #[test]
+    fn should_keep_unknown_interactions() {
+        let payload = r#"{"type":"message_action","callback_id":"x"}"#;
+
+        let interaction = serde_json::from_str::<Interaction>(payload).unwrap();
+
+        assert_eq!(interaction, Interaction::Unknown);
+        assert_eq!(interaction.handler_key(), None);
+    }
+}
+
+#[cfg(test)]
+mod test_interaction_registry {
+    use std::sync::Arc;
+
+    use crate::cache::MemoryEmployeeCache;
+    use crate::interactivity::{
+        registry::{InteractionKind, InteractionRegistry},
+        Interaction, InteractionContext, InteractionResponse, InteractionUser,
+    };
+    use crate::pg_database::pool::unavailable_pool;
+    use crate::slack_api::SlackClient;
+
+    fn context() -> InteractionContext {
+        InteractionContext {
+            pool: unavailable_pool(),
+            cache: Arc::new(MemoryEmployeeCache::default()),
+            slack: Arc::new(SlackClient::new("xoxb-test", "http://127.0.0.1:9")),
+        }
+    }
+
+    fn shortcut(callback_id: &str) -> Interaction {
+        Interaction::Shortcut {
+            user: InteractionUser {
+                id: "U123".to_string(),
+            },
+            trigger_id: "1.2.abc".to_string(),
+            callback_id: callback_id.to_string(),
+        }
+    }
+
+    fn reject(_: InteractionContext, _: Interaction) -> InteractionResponse {
+        InteractionResponse::Invalid(())
+    }
+
+    #[test]
+    fn should_dispatch_by_kind_and_id() {
+        let registry =
+            InteractionRegistry::default().register(InteractionKind::Shortcut, "nuevos", reject);
+
+        assert_eq!(
+            registry.dispatch(context(), shortcut("nuevos")),
+            InteractionResponse::Invalid(())
+        );
+        assert_eq!(
+            registry.dispatch(context(), shortcut("otro")),
+            InteractionResponse::Ack(())
+        );
+    }
+
+    #[test]
+    fn should_not_mix_kinds_with_the_same_id() {
+        let registry = InteractionRegistry::default().register(
+            InteractionKind::ViewSubmission,
+            "nuevos",
+            reject,
+        );
+
+        assert_eq!(
+            registry.dispatch(context(), shortcut("nuevos")),
+            InteractionResponse::Ack(())
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_nuevos_range {
+    use std::collections::HashMap;
+
+    use chrono_tz::Tz;
+    use serde_json::json;
+
+    use crate::interactivity::{nuevos_range::parse_range_submission, View, ViewState};
+    use crate::slash_command::nuevos::{NuevosQuery, RangeModalMetadata};
+
+    const METADATA: &str = r#"{"response_url":"https://hooks.slack.com/commands/T1/2/3","include_inactive":true,"in_channel":false}"#;
+
+    fn view(from: Option<&str>, to: Option<&str>, countries: Option<&str>) -> View {
+        let date = |date: Option<&str>| json!({ "type": "datepicker", "selected_date": date });
+        View {
+            id: "V1".to_string(),
+            callback_id: "nuevos_rango".to_string(),
+            private_metadata: METADATA.to_string(),
+            state: ViewState {
+                values: HashMap::from([
+                    (
+                        "desde".to_string(),
+                        HashMap::from([("valor".to_string(), date(from))]),
+                    ),
+                    (
+                        "hasta".to_string(),
+                        HashMap::from([("valor".to_string(), date(to))]),
+                    ),
+                    (
+                        "pais".to_string(),
+                        HashMap::from([(
+                            "valor".to_string(),
+                            json!({ "type": "plain_text_input", "value": countries }),
+                        )]),
+                    ),
+                ]),
+            },
+        }
+    }
+
+    #[test]
+    fn should_query_whole_days_and_keep_command_options() {
+        let (query, metadata) = parse_range_submission(&view(
+            Some("2024-01-01"),
+            Some("2024-12-31"),
+            Some(" Argentina, chile ,"),
+        ))
+        .unwrap();
+
+        assert_eq!(
+            query,
+            NuevosQuery {
+                from: Some(1704067200),
+                to: 1735689599,
+                include_inactive: true,
+                in_channel: false,
+                countries: vec!["argentina".to_string(), "chile".to_string()],
+                group_by_country: false,
+                tz: Tz::UTC,
+                page: 0,
+            }
+        );
+        assert_eq!(
+            metadata,
+            RangeModalMetadata {
+                response_url: "https://hooks.slack.com/commands/T1/2/3".to_string(),
+                include_inactive: true,
+                in_channel: false,
+                group_by_country: false,
+                tz: Tz::UTC,
+            }
+        );
+    }
+
+    #[test]
+    fn should_query_whole_days_where_the_user_is() {
+        let mut view = view(Some("2024-03-31"), Some("2024-03-31"), None);
+        view.private_metadata = r#"{"response_url":"https://hooks.slack.com/commands/T1/2/3","include_inactive":false,"in_channel":false,"tz":"America/Argentina/Buenos_Aires"}"#.to_string();
+
+        let (query, _) = parse_range_submission(&view).unwrap();
+
+        assert_eq!(query.from, Some(1711854000)); // 2024-03-31 03:00:00 UTC-0
+        assert_eq!(query.to, 1711940399); // 2024-04-01 02:59:59 UTC-0
+        assert_eq!(query.tz, chrono_tz::America::Argentina::Buenos_Aires);
+    }
+
+    #[test]
+    fn should_allow_any_country() {
+        let (query, _) =
+            parse_range_submission(&view(Some("2024-01-01"), Some("2024-01-01"), None)).unwrap();
+
+        assert_eq!(query.countries, Vec::<String>::new());
+    }
+
+    #[test]
+    fn should_show_errors_under_the_wrong_date() {
+        let reversed = parse_range_submission(&view(Some("2024-02-01"), Some("2024-01-01"), None));
+        let missing = parse_range_submission(&view(None, Some("2024-01-01"), None));
+
+        assert_eq!(
+            reversed.unwrap_err().keys().collect::<Vec<_>>(),
+            vec!["hasta"]
+        );
+        assert_eq!(
+            missing.unwrap_err().keys().collect::<Vec<_>>(),
+            vec!["desde"]
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_interactivity_route {
+    use chrono::Utc;
+    use rocket::{
+        http::{ContentType, Header, RawStr, Status},
+        local::blocking::Client,
+    };
+    use serde_json::json;
+
+    use crate::authenticate::{sign, SigningSecret, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+    use crate::cache::{MemoryEmployeeCache, SharedEmployeeCache};
+    use crate::interactivity::{interaction_registry, interactivity_route};
+    use crate::pg_database::pool::unavailable_pool;
+    use crate::slack_api::{ChannelSlackApi, SharedSlackApi, SlackCall};
+    use std::{
+        sync::{mpsc::Receiver, Arc},
+        time::Duration,
+    };
+
+    const SECRET: &str = "test_signing_secret";
+
+    fn client() -> Client {
+        client_with_calls().0
+    }
+
+    /// The database is down.
+    fn client_with_calls() -> (Client, Receiver<SlackCall>) {
+        let (slack, calls) = ChannelSlackApi::new(None);
+        let rocket = rocket::build()
+            .manage(SigningSecret(SECRET.to_string()))
+            .manage(unavailable_pool())
+            .manage(Arc::new(slack) as SharedSlackApi)
+            .manage(Arc::new(MemoryEmployeeCache::default()) as SharedEmployeeCache)
+            .manage(interaction_registry())
+            .mount("/", routes![interactivity_route]);
+        (Client::tracked(rocket).unwrap(), calls)
+    }
+
+    fn post_payload(client: &Client, payload: &str) -> Status {
+        post_payload_with_body(client, payload).0
+    }
+
+    fn post_payload_with_body(client: &Client, payload: &str) -> (Status, String) {
+        let ts = Utc::now().timestamp().to_string();
+        let body = format!("payload={}", RawStr::new(payload).percent_encode());
+
+        let res = client
+            .post("/interactivity")
+            .header(ContentType::Form)
+            .header(Header::new(TIMESTAMP_HEADER, ts.clone()))
+            .header(Header::new(SIGNATURE_HEADER, sign(SECRET, &ts, &body)))
+            .body(body)
+            .dispatch();
+
+        (res.status(), res.into_string().unwrap_or_default())
+    }
+
+    #[test]
+    fn should_query_the_page_of_a_clicked_button() {
+        let (client, calls) = client_with_calls();
+        let value = r#"{"from":1704067200,"to":1735689599,"include_inactive":false,"in_channel":false,"page":1}"#;
+        let payload = json!({
+            "type": "block_actions",
+            "user": { "id": "U123" },
+            "trigger_id": "1.2.abc",
+            "response_url": "https://hooks.slack.com/actions/T1/2/3",
+            "actions": [{ "type": "button", "action_id": "nuevos_page", "value": value }]
+        });
+
+        let status = post_payload(&client, &payload.to_string());
+
+        assert_eq!(status, Status::Ok);
+        match calls.recv_timeout(Duration::from_secs(5)) {
+            Ok(SlackCall::Respond {
+                response_url,
+                response,
+            }) => {
+                assert_eq!(response_url, "https://hooks.slack.com/actions/T1/2/3");
+                assert!(response
+                    .text
+                    .contains("No pude conectarme a la base de datos"));
+            }
+            other => panic!("unexpected call {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_keep_the_modal_open_on_invalid_dates() {
+        let client = client();
+        let metadata = r#"{"response_url":"https://hooks.slack.com/commands/T1/2/3","include_inactive":false,"in_channel":false}"#;
+        let payload = json!({
+            "type": "view_submission",
+            "user": { "id": "U123" },
+            "view": {
+                "id": "V1",
+                "callback_id": "nuevos_rango",
+                "private_metadata": metadata,
+                "state": { "values": {
+                    "desde": { "valor": { "type": "datepicker", "selected_date": "2024-02-01" } },
+                    "hasta": { "valor": { "type": "datepicker", "selected_date": "2024-01-01" } }
+                } }
+            }
+        });
+
+        let (status, body) = post_payload_with_body(&client, &payload.to_string());
+
+        assert_eq!(status, Status::Ok);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+            json!({
+                "response_action": "errors",
+                "errors": { "hasta": "Tiene que ser igual o posterior a la fecha de inicio." }
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_payloads_that_are_not_json() {
+        let client = client();
+
+        assert_eq!(post_payload(&client, "not json"), Status::BadRequest);
+    }
+}
@@ -1,17 +1,34 @@
 mod authenticate;
+mod cache;
 mod event;
+mod interactivity;
 mod models;
 mod pg_database;
+mod scheduler;
 mod schema;
+mod slack_api;
 mod slash_command;
 mod utils;
 
-use event::event_route;
-use pg_database::establish_connection;
-use rocket::{Build, Config, Rocket};
+use authenticate::SigningSecret;
+use cache::{invalidate, RedisEmployeeCache, SharedEmployeeCache};
+use event::{dedupe::ProcessedEvents, event_route, team_join::TeamJoinConfig};
+use interactivity::{interaction_registry, interactivity_route};
+use pg_database::pool::{init_pool, DbPool};
+use rocket::{fairing::AdHoc, Build, Config, Rocket};
+use scheduler::{
+    spawn_scheduler,
+    store::{RedisJobStore, SharedJobStore},
+};
+use slack_api::{SharedSlackApi, SlackClient};
+use slash_command::announcements::announcements_command_route;
+use slash_command::buddy::buddy_command_route;
+use slash_command::checklist::checklist_command_route;
 use slash_command::help_command_route;
+use slash_command::offboard::offboard_command_route;
+use slash_command::project::project_command_route;
 use slash_command::slash_command_route;
-use std::{env, net::IpAddr};
+use std::{env, net::IpAddr, sync::Arc};
 use utils::load_env::load_env;
 
 #[macro_use]
@@ -19,15 +36,10 @@ extern crate rocket;
 extern crate dotenv;
 extern crate redis;
 
-#[macro_use]
-extern crate diesel;
-#[macro_use]
-extern crate diesel_migrations;
-
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
 
-fn init_rocket() -> Rocket<Build> {
+fn init_rocket(pool: DbPool, cache: SharedEmployeeCache) -> Rocket<Build> {
     let config = Config {
         port: env::var("APP_PORT")
             .unwrap()
@@ -40,16 +52,46 @@ fn init_rocket() -> Rocket<Build> {
         ..Config::debug_default()
     };
 
-    rocket::build().configure(&config).mount(
-        env::var("APP_BASE_ROUTE").unwrap(),
-        routes![event_route, slash_command_route, help_command_route],
-    )
+    rocket::build()
+        .configure(&config)
+        .manage(SigningSecret::from_env())
+        .manage(pool)
+        .manage(ProcessedEvents::default())
+        .manage(Arc::new(SlackClient::from_env()) as SharedSlackApi)
+        .manage(Arc::new(RedisJobStore::from_env()) as SharedJobStore)
+        .manage(cache)
+        .manage(TeamJoinConfig::from_env())
+        .manage(interaction_registry())
+        .attach(AdHoc::on_liftoff("Scheduler", |rocket| {
+            Box::pin(async move {
+                let store = rocket.state::<SharedJobStore>().unwrap().clone();
+                let slack = rocket.state::<SharedSlackApi>().unwrap().clone();
+                let pool = rocket.state::<DbPool>().unwrap().clone();
+                spawn_scheduler(store, slack, pool);
+            })
+        }))
+        .mount(
+            env::var("APP_BASE_ROUTE").unwrap(),
+            routes![
+                event_route,
+                interactivity_route,
+                slash_command_route,
+                help_command_route,
+                offboard_command_route,
+                project_command_route,
+                announcements_command_route,
+                checklist_command_route,
+                buddy_command_route
+            ],
+        )
 }
 
 #[launch]
 fn init() -> _ {
     load_env();
-    let mut connection = establish_connection();
+    let pool = init_pool();
+    let cache: SharedEmployeeCache = Arc::new(RedisEmployeeCache::from_env());
+    let mut connection = pool.get().expect("Failed to get a database connection");
 
     connection
         .run_pending_migrations(MIGRATIONS)
@@ -60,8 +102,9 @@ fn init() -> _ {
         let command = &args[1];
         let file_path = &args[2];
         if command == "seed-db" {
-            match pg_database::db_seeder::seed_database(file_path) {
+            match pg_database::db_seeder::seed_database(&mut connection, file_path) {
                 Ok(_) => {
+                    invalidate(cache.as_ref());
                     println!("Database seeded successfully.");
                 }
                 Err(e) => {
@@ -71,5 +114,6 @@ fn init() -> _ {
         }
     }
 
-    init_rocket()
+    drop(connection);
+    init_rocket(pool, cache)
 }
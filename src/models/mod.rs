@@ -1,20 +1,48 @@
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDate, Utc};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Queryable, Selectable, Insertable, Deserialize, Serialize)]
+#[derive(Queryable, Selectable, Insertable, Deserialize, Serialize, Clone, Debug, PartialEq)]
 #[diesel(table_name = crate::schema::employees)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct Employee {
     pub id: String,
-    pub email: String,
+    /// Slack doesn't share one for every member.
+    pub email: Option<String>,
     pub full_name: String,
     pub country: Option<String>,
-    pub join_date: NaiveDateTime,
+    pub join_date: DateTime<Utc>,
+    pub active: bool,
+    /// The last day, as an admin typed it or the UTC day Slack deleted the
+    /// account. Days aren't moved to whoever reads them, unlike `join_date`.
+    pub left_date: Option<NaiveDate>,
 }
 
-#[derive(Queryable, Selectable)]
+#[cfg(test)]
+pub fn test_employee(id: &str, join_ts: i64) -> Employee {
+    Employee {
+        id: id.to_string(),
+        email: Some(format!("{}@example.com", id.to_lowercase())),
+        full_name: id.to_string(),
+        country: None,
+        join_date: DateTime::from_timestamp(join_ts, 0).unwrap(),
+        active: true,
+        left_date: None,
+    }
+}
+
+/// Fields that follow the Slack profile after the employee joined.
+#[derive(AsChangeset)]
+#[diesel(table_name = crate::schema::employees)]
+/// `None` fields keep what is stored.
+pub struct EmployeeProfile {
+    pub email: Option<String>,
+    pub full_name: Option<String>,
+    pub country: Option<String>,
+}
+
+#[derive(Queryable, Selectable, Clone, Debug, PartialEq)]
 #[diesel(table_name = crate::schema::projects)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct Projects {
@@ -23,11 +51,81 @@ pub struct Projects {
     pub admin_id: String,
 }
 
-#[derive(Queryable, Selectable)]
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::projects)]
+pub struct NewProject {
+    pub name: String,
+    pub admin_id: String,
+}
+
+#[derive(Queryable, Selectable, Insertable, Clone, Debug, PartialEq)]
 #[diesel(table_name = crate::schema::onboardees)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct Onboardees {
     pub project_id: Uuid,
     pub employee_id: String,
-    pub onboarding_date: NaiveDateTime,
+    /// A day, like `Employee.left_date`.
+    pub onboarding_date: NaiveDate,
+}
+
+/// A step of a project's onboarding checklist, numbered by `position`.
+#[derive(Queryable, Selectable, Clone, Debug, PartialEq)]
+#[diesel(table_name = crate::schema::checklist_tasks)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ChecklistTask {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub title: String,
+    pub position: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::checklist_tasks)]
+pub struct NewChecklistTask {
+    pub project_id: Uuid,
+    pub title: String,
+    pub position: i32,
+}
+
+/// A checklist task an onboardee already finished. Pending tasks have no row.
+#[derive(Queryable, Selectable, Insertable, Clone, Debug, PartialEq)]
+#[diesel(table_name = crate::schema::task_completions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct TaskCompletion {
+    pub task_id: Uuid,
+    pub employee_id: String,
+    pub completed_by: String,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// `buddy_id` accompanies `employee_id` through their onboarding.
+#[derive(Queryable, Selectable, Insertable, Clone, Debug, PartialEq)]
+#[diesel(table_name = crate::schema::buddies)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Buddy {
+    pub employee_id: String,
+    pub buddy_id: String,
+    pub assigned_at: DateTime<Utc>,
+}
+
+/// Single row that drives the `team_join` announcement, edited with `/anuncios`.
+#[derive(Queryable, Selectable, Clone, Debug, PartialEq)]
+#[diesel(table_name = crate::schema::announcement_settings)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AnnouncementSettings {
+    pub id: i32,
+    pub enabled: bool,
+    pub channel_id: Option<String>,
+    pub template: String,
+    pub intro: Option<String>,
+}
+
+/// Fields left as `None` are not touched.
+#[derive(AsChangeset, Default, Debug, PartialEq)]
+#[diesel(table_name = crate::schema::announcement_settings)]
+pub struct AnnouncementSettingsChange {
+    pub enabled: Option<bool>,
+    pub channel_id: Option<Option<String>>,
+    pub template: Option<String>,
+    pub intro: Option<Option<String>>,
 }
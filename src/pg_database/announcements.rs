@@ -0,0 +1,27 @@
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+use super::DbError;
+use crate::models::{AnnouncementSettings, AnnouncementSettingsChange};
+use crate::schema::announcement_settings;
+
+const SETTINGS_ID: i32 = 1;
+
+pub fn get_announcement_settings(conn: &mut PgConnection) -> Result<AnnouncementSettings, DbError> {
+    announcement_settings::table
+        .find(SETTINGS_ID)
+        .select(AnnouncementSettings::as_select())
+        .first(conn)
+        .map_err(DbError::from)
+}
+
+pub fn update_announcement_settings(
+    conn: &mut PgConnection,
+    change: &AnnouncementSettingsChange,
+) -> Result<AnnouncementSettings, DbError> {
+    diesel::update(announcement_settings::table.find(SETTINGS_ID))
+        .set(change)
+        .returning(AnnouncementSettings::as_returning())
+        .get_result(conn)
+        .map_err(DbError::from)
+}
@@ -0,0 +1,165 @@
+use chrono::{DateTime, Duration, Utc};
+use diesel::dsl::count;
+use diesel::pg::{upsert::excluded, PgConnection};
+use diesel::prelude::*;
+
+use super::DbError;
+use crate::models::{Buddy, Employee};
+use crate::schema::{buddies, employees};
+
+/// Sets the buddy of `employee_id`, replacing any previous one.
+pub fn assign_buddy(
+    conn: &mut PgConnection,
+    employee_id: &str,
+    buddy_id: &str,
+    assigned_at: DateTime<Utc>,
+) -> Result<Buddy, DbError> {
+    let buddy = Buddy {
+        employee_id: employee_id.to_string(),
+        buddy_id: buddy_id.to_string(),
+        assigned_at,
+    };
+
+    diesel::insert_into(buddies::table)
+        .values(&buddy)
+        .on_conflict(buddies::employee_id)
+        .do_update()
+        .set((
+            buddies::buddy_id.eq(excluded(buddies::buddy_id)),
+            buddies::assigned_at.eq(excluded(buddies::assigned_at)),
+        ))
+        .returning(Buddy::as_returning())
+        .get_result(conn)
+        .map_err(DbError::from)
+}
+
+pub fn get_buddy(conn: &mut PgConnection, employee_id: &str) -> Result<Option<Buddy>, DbError> {
+    buddies::table
+        .find(employee_id)
+        .select(Buddy::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DbError::from)
+}
+
+/// People `buddy_id` is currently accompanying.
+pub fn get_buddy_mentees(conn: &mut PgConnection, buddy_id: &str) -> Result<Vec<Buddy>, DbError> {
+    buddies::table
+        .filter(buddies::buddy_id.eq(buddy_id))
+        .order(buddies::assigned_at.asc())
+        .select(Buddy::as_select())
+        .load(conn)
+        .map_err(DbError::from)
+}
+
+/// Mentees count against their buddy while still onboarding, like the last
+/// check-in.
+pub const ONBOARDING_DAYS: i64 = 30;
+/// Buddies should know their way around already.
+pub const MIN_BUDDY_TENURE_DAYS: i64 = 90;
+
+/// Picks the active employee from `country`, with at least
+/// `MIN_BUDDY_TENURE_DAYS` at `now`, who has the fewest mentees still
+/// onboarding; the most senior one on ties. `employee_id` itself is never
+/// picked.
+pub fn find_buddy_candidate(
+    conn: &mut PgConnection,
+    country: &str,
+    employee_id: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<Employee>, DbError> {
+    let mentees = diesel::alias!(employees as mentees);
+    let onboarding_mentees = mentees
+        .filter(
+            mentees
+                .field(employees::join_date)
+                .ge(now - Duration::days(ONBOARDING_DAYS)),
+        )
+        .select(mentees.field(employees::id));
+
+    employees::table
+        .left_join(
+            buddies::table.on(buddies::buddy_id
+                .eq(employees::id)
+                .and(buddies::employee_id.eq_any(onboarding_mentees))),
+        )
+        .filter(employees::country.eq(country))
+        .filter(employees::active.eq(true))
+        .filter(employees::id.ne(employee_id))
+        .filter(employees::join_date.le(now - Duration::days(MIN_BUDDY_TENURE_DAYS)))
+        .group_by(employees::id)
+        .order((
+            count(buddies::employee_id.nullable()).asc(),
+            employees::join_date.asc(),
+        ))
+        .select(Employee::as_select())
+        .first(conn)
+        .optional()
+        .map_err(DbError::from)
+}
+
+#[cfg(test)]
+mod test_find_buddy_candidate {
+    use chrono::{DateTime, Utc};
+    use diesel::pg::PgConnection;
+
+    use super::{assign_buddy, find_buddy_candidate};
+    use crate::models::test_employee;
+    use crate::pg_database::{save_employee, test_connection, SaveMode};
+
+    const NOW: i64 = 1717200000; // 2024-06-01 UTC-0
+
+    fn employee(conn: &mut PgConnection, id: &str, join_ts: i64, country: Option<&str>) {
+        let mut employee = test_employee(id, join_ts);
+        employee.country = country.map(String::from);
+        save_employee(conn, &employee, SaveMode::Upsert).unwrap();
+    }
+
+    fn assigned_at() -> DateTime<Utc> {
+        DateTime::from_timestamp(NOW, 0).unwrap()
+    }
+
+    #[test]
+    fn should_only_count_mentees_still_onboarding() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        let conn = &mut conn;
+        employee(conn, "BBUSY", 1672531200, Some("testlandia")); // 2023-01-01
+        employee(conn, "BFREE", 1685577600, Some("testlandia")); // 2023-06-01
+        employee(conn, "MNEW", 1716595200, None); // 2024-05-25
+        employee(conn, "MOLD1", 1688169600, None); // 2023-07-01
+        employee(conn, "MOLD2", 1688169600, None);
+        assign_buddy(conn, "MNEW", "BBUSY", assigned_at()).unwrap();
+        assign_buddy(conn, "MOLD1", "BFREE", assigned_at()).unwrap();
+        assign_buddy(conn, "MOLD2", "BFREE", assigned_at()).unwrap();
+
+        let candidate = find_buddy_candidate(
+            conn,
+            "testlandia",
+            "UNUEVO",
+            DateTime::from_timestamp(NOW, 0).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(candidate.map(|e| e.id), Some("BFREE".to_string()));
+    }
+
+    #[test]
+    fn should_skip_people_who_just_joined() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        employee(&mut conn, "BNEW", 1716163200, Some("testlandia")); // 2024-05-20
+
+        let candidate = find_buddy_candidate(
+            &mut conn,
+            "testlandia",
+            "UNUEVO",
+            DateTime::from_timestamp(NOW, 0).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(candidate, None);
+    }
+}
@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use super::DbError;
+use crate::models::{ChecklistTask, NewChecklistTask, TaskCompletion};
+use crate::schema::{checklist_tasks, projects, task_completions};
+
+/// Appends a task at the end of the project's checklist. The project row is
+/// locked until the insert, so concurrent adds take consecutive positions.
+pub fn add_checklist_task(
+    conn: &mut PgConnection,
+    project_id: Uuid,
+    title: &str,
+) -> Result<ChecklistTask, DbError> {
+    conn.transaction(|conn| {
+        projects::table
+            .find(project_id)
+            .select(projects::id)
+            .for_update()
+            .first::<Uuid>(conn)?;
+
+        let last_position: Option<i32> = checklist_tasks::table
+            .filter(checklist_tasks::project_id.eq(project_id))
+            .select(diesel::dsl::max(checklist_tasks::position))
+            .first(conn)?;
+
+        let new_task = NewChecklistTask {
+            project_id,
+            title: title.to_string(),
+            position: last_position.unwrap_or(0) + 1,
+        };
+
+        diesel::insert_into(checklist_tasks::table)
+            .values(&new_task)
+            .returning(ChecklistTask::as_returning())
+            .get_result(conn)
+    })
+    .map_err(DbError::from)
+}
+
+pub fn get_checklist_task(
+    conn: &mut PgConnection,
+    project_id: Uuid,
+    position: i32,
+) -> Result<ChecklistTask, DbError> {
+    checklist_tasks::table
+        .filter(checklist_tasks::project_id.eq(project_id))
+        .filter(checklist_tasks::position.eq(position))
+        .select(ChecklistTask::as_select())
+        .first(conn)
+        .map_err(DbError::from)
+}
+
+/// Every task of the project, with its completion by `employee_id` if done.
+pub fn get_checklist_progress(
+    conn: &mut PgConnection,
+    project_id: Uuid,
+    employee_id: &str,
+) -> Result<Vec<(ChecklistTask, Option<TaskCompletion>)>, DbError> {
+    checklist_tasks::table
+        .left_join(
+            task_completions::table.on(task_completions::task_id
+                .eq(checklist_tasks::id)
+                .and(task_completions::employee_id.eq(employee_id))),
+        )
+        .filter(checklist_tasks::project_id.eq(project_id))
+        .order(checklist_tasks::position.asc())
+        .select((
+            ChecklistTask::as_select(),
+            Option::<TaskCompletion>::as_select(),
+        ))
+        .load(conn)
+        .map_err(DbError::from)
+}
+
+/// Marks the task done. Completing it again keeps the first completion.
+pub fn complete_task(
+    conn: &mut PgConnection,
+    task_id: Uuid,
+    employee_id: &str,
+    completed_by: &str,
+    completed_at: DateTime<Utc>,
+) -> Result<TaskCompletion, DbError> {
+    let completion = TaskCompletion {
+        task_id,
+        employee_id: employee_id.to_string(),
+        completed_by: completed_by.to_string(),
+        completed_at,
+    };
+
+    diesel::insert_into(task_completions::table)
+        .values(&completion)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+
+    task_completions::table
+        .find((task_id, employee_id))
+        .select(TaskCompletion::as_select())
+        .first(conn)
+        .map_err(DbError::from)
+}
+
+#[cfg(test)]
+mod test_add_checklist_task {
+    use super::add_checklist_task;
+    use crate::models::test_employee;
+    use crate::pg_database::{
+        projects::create_project, save_employee, test_connection, DbError, SaveMode,
+    };
+
+    #[test]
+    fn should_append_tasks_in_order() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+        save_employee(
+            &mut conn,
+            &test_employee("UADMIN", 1704067200),
+            SaveMode::Upsert,
+        )
+        .unwrap();
+        let project = create_project(&mut conn, "Checklist de prueba", "UADMIN").unwrap();
+
+        let first = add_checklist_task(&mut conn, project.id, "repo").unwrap();
+        let second = add_checklist_task(&mut conn, project.id, "pr").unwrap();
+
+        assert_eq!((first.position, second.position), (1, 2));
+    }
+
+    #[test]
+    fn should_not_add_tasks_to_missing_projects() {
+        let Some(mut conn) = test_connection() else {
+            return;
+        };
+
+        assert_eq!(
+            add_checklist_task(&mut conn, uuid::Uuid::nil(), "repo"),
+            Err(DbError::NotFound)
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use crate::models::Employee;
-use crate::pg_database::save_employee;
-use chrono::NaiveDateTime;
+use crate::pg_database::{save_employee, SaveMode};
+use chrono::DateTime;
+use diesel::pg::PgConnection;
 use serde::Deserialize;
 use std::error::Error;
 use std::fs;
@@ -23,19 +24,21 @@ fn load_seed_employees(file_path: &str) -> Result<Vec<SeedEmployee>, Box<dyn Err
 fn to_employee(seed_employee: &SeedEmployee) -> Employee {
     Employee {
         id: seed_employee.id.clone(),
-        email: seed_employee.email.clone(),
+        email: Some(seed_employee.email.clone()),
         full_name: seed_employee.name.clone(),
         country: Some(seed_employee.country.clone()),
-        join_date: NaiveDateTime::from_timestamp_opt(seed_employee.date, 0).unwrap(),
+        join_date: DateTime::from_timestamp(seed_employee.date, 0).unwrap(),
+        active: true,
+        left_date: None,
     }
 }
-pub fn seed_database(file_path: &str) -> Result<(), Box<dyn Error>> {
+pub fn seed_database(conn: &mut PgConnection, file_path: &str) -> Result<(), Box<dyn Error>> {
     let seed_employees = load_seed_employees(file_path)?;
 
     for seed_employee in seed_employees {
         let employee = to_employee(&seed_employee);
 
-        save_employee(&employee);
+        save_employee(conn, &employee, SaveMode::Insert)?;
     }
 
     Ok(())
@@ -1,48 +1,254 @@
-use chrono::NaiveDateTime;
-use diesel::pg::PgConnection;
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::pg::{upsert::excluded, PgConnection};
 use diesel::prelude::*;
-use dotenv::dotenv;
-use std::env;
+use diesel::r2d2::PoolError;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use std::fmt;
 
-use crate::models::Employee;
+use crate::models::{Employee, EmployeeProfile};
 use crate::schema::employees;
 
+pub mod announcements;
+pub mod buddies;
+pub mod checklists;
 pub mod db_seeder;
+pub mod pool;
+pub mod projects;
 
-pub fn save_employee(employee: &Employee) -> Employee {
+sql_function!(fn lower(x: diesel::sql_types::Text) -> diesel::sql_types::Text);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DbError {
+    UniqueViolation(String),
+    NotFound,
+    Connection(String),
+    Query(String),
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DbError::UniqueViolation(detail) => write!(f, "unique violation: {}", detail),
+            DbError::NotFound => write!(f, "record not found"),
+            DbError::Connection(detail) => write!(f, "connection failure: {}", detail),
+            DbError::Query(detail) => write!(f, "query failure: {}", detail),
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+impl From<DieselError> for DbError {
+    fn from(e: DieselError) -> Self {
+        match e {
+            DieselError::NotFound => DbError::NotFound,
+            DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, info) => {
+                DbError::UniqueViolation(info.message().to_string())
+            }
+            DieselError::DatabaseError(DatabaseErrorKind::ClosedConnection, info) => {
+                DbError::Connection(info.message().to_string())
+            }
+            e => DbError::Query(e.to_string()),
+        }
+    }
+}
+
+impl From<PoolError> for DbError {
+    fn from(e: PoolError) -> Self {
+        DbError::Connection(e.to_string())
+    }
+}
+
+/// How `save_employee` treats an employee whose `id` is already stored.
+pub enum SaveMode {
+    /// Fail with `DbError::UniqueViolation`.
+    Insert,
+    /// Refresh the profile fields, keeping the original `join_date`.
+    Upsert,
+}
+
+pub fn save_employee(
+    conn: &mut PgConnection,
+    employee: &Employee,
+    mode: SaveMode,
+) -> Result<Employee, DbError> {
     let new_employee = Employee {
         id: employee.id.clone(),
         email: employee.email.clone(),
         full_name: employee.full_name.clone(),
         country: employee.country.clone(),
         join_date: employee.join_date,
+        active: employee.active,
+        left_date: employee.left_date,
     };
 
-    let conn = &mut establish_connection();
-    diesel::insert_into(employees::table)
-        .values(&new_employee)
+    let insert = diesel::insert_into(employees::table).values(&new_employee);
+
+    match mode {
+        SaveMode::Insert => insert.returning(Employee::as_returning()).get_result(conn),
+        SaveMode::Upsert => insert
+            .on_conflict(employees::id)
+            .do_update()
+            .set((
+                employees::email.eq(excluded(employees::email)),
+                employees::full_name.eq(excluded(employees::full_name)),
+                employees::country.eq(excluded(employees::country)),
+            ))
+            .returning(Employee::as_returning())
+            .get_result(conn),
+    }
+    .map_err(DbError::from)
+}
+
+pub fn update_employee_profile(
+    conn: &mut PgConnection,
+    employee_id: &str,
+    profile: &EmployeeProfile,
+) -> Result<Employee, DbError> {
+    // Diesel refuses an empty changeset, as sent for deleted users.
+    if profile.email.is_none() && profile.full_name.is_none() && profile.country.is_none() {
+        return get_employee(conn, employee_id);
+    }
+
+    diesel::update(employees::table.find(employee_id))
+        .set(profile)
         .returning(Employee::as_returning())
         .get_result(conn)
-        .expect("Error saving new employee")
+        .map_err(DbError::from)
 }
 
-pub fn get_employee_by_ts_range(from_ts: NaiveDateTime, to_ts: NaiveDateTime) -> Vec<Employee> {
-    let conn = &mut establish_connection();
+pub fn get_employee(conn: &mut PgConnection, employee_id: &str) -> Result<Employee, DbError> {
     employees::table
+        .find(employee_id)
+        .select(Employee::as_select())
+        .first(conn)
+        .map_err(DbError::from)
+}
+
+/// Who deactivated an employee.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LeftBy {
+    /// Slack deleted the account, and may restore it.
+    Slack,
+    /// An admin ran `/baja`, which only another admin should undo.
+    Admin,
+}
+
+/// Deactivates an employee. Slack keeps an already recorded `left_date`, so
+/// repeated deletion events don't move it, while an admin's date always wins
+/// and makes the offboarding theirs.
+pub fn mark_employee_left(
+    conn: &mut PgConnection,
+    employee_id: &str,
+    left_date: NaiveDate,
+    left_by: LeftBy,
+) -> Result<Employee, DbError> {
+    let overrides = (left_by == LeftBy::Admin).into_sql::<diesel::sql_types::Bool>();
+    let updated = diesel::update(
+        employees::table
+            .find(employee_id)
+            .filter(employees::left_date.is_null().or(overrides)),
+    )
+    .set((
+        employees::active.eq(false),
+        employees::left_date.eq(left_date),
+        employees::left_by_slack.eq(left_by == LeftBy::Slack),
+    ))
+    .returning(Employee::as_returning())
+    .get_result(conn)
+    .optional()?;
+
+    match updated {
+        Some(employee) => Ok(employee),
+        None => get_employee(conn, employee_id),
+    }
+}
+
+/// Reactivates an employee Slack had deactivated. Offboardings from `/baja`
+/// are kept, since any profile edit also arrives as an active `user_change`.
+pub fn mark_employee_active(
+    conn: &mut PgConnection,
+    employee_id: &str,
+) -> Result<Employee, DbError> {
+    let updated = diesel::update(
+        employees::table
+            .find(employee_id)
+            .filter(employees::left_by_slack.eq(true)),
+    )
+    .set((
+        employees::active.eq(true),
+        employees::left_date.eq(None::<NaiveDate>),
+        employees::left_by_slack.eq(false),
+    ))
+    .returning(Employee::as_returning())
+    .get_result(conn)
+    .optional()?;
+
+    match updated {
+        Some(employee) => Ok(employee),
+        None => get_employee(conn, employee_id),
+    }
+}
+
+/// Optional narrowing of `get_employee_by_ts_range`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EmployeeFilter {
+    /// Also list people who already left, so templates can annotate them.
+    pub include_inactive: bool,
+    /// Only people from these countries, lowercased like `Employee.country`.
+    /// Empty means any country.
+    pub countries: Vec<String>,
+}
+
+/// When the first employee joined, if anyone did.
+pub fn get_first_join_date(conn: &mut PgConnection) -> Result<Option<DateTime<Utc>>, DbError> {
+    employees::table
+        .select(diesel::dsl::min(employees::join_date))
+        .first(conn)
+        .map_err(DbError::from)
+}
+
+pub fn get_employee_by_ts_range(
+    conn: &mut PgConnection,
+    from_ts: DateTime<Utc>,
+    to_ts: DateTime<Utc>,
+    filter: &EmployeeFilter,
+) -> Result<Vec<Employee>, DbError> {
+    let mut query = employees::table
         .filter(
             employees::join_date
                 .ge(from_ts)
                 .and(employees::join_date.le(to_ts)),
         )
-        .load::<Employee>(conn)
-        .expect("Error loading employees")
+        .select(Employee::as_select())
+        .into_boxed();
+
+    if !filter.include_inactive {
+        query = query.filter(employees::active.eq(true));
+    }
+    if !filter.countries.is_empty() {
+        query = query.filter(employees::country.eq_any(&filter.countries));
+    }
+
+    query.load::<Employee>(conn).map_err(DbError::from)
 }
 
-pub fn establish_connection() -> PgConnection {
-    dotenv().ok();
+/// A connection to `TEST_DATABASE_URL` in a transaction that is never
+/// committed, or `None` to skip the test when that variable isn't set.
+#[cfg(test)]
+pub fn test_connection() -> Option<PgConnection> {
+    use diesel_migrations::MigrationHarness;
+    use std::sync::Once;
 
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    static MIGRATE: Once = Once::new();
 
-    PgConnection::establish(&database_url)
-        .unwrap_or_else(|_| panic!("Error connecting to {}", database_url))
+    let url = std::env::var("TEST_DATABASE_URL").ok()?;
+    let mut conn = PgConnection::establish(&url).expect("TEST_DATABASE_URL is not reachable");
+    MIGRATE.call_once(|| {
+        conn.run_pending_migrations(crate::MIGRATIONS)
+            .expect("test database migrations failed");
+    });
+    conn.begin_test_transaction()
+        .expect("test transaction not started");
+    Some(conn)
 }
@@ -0,0 +1,99 @@
+use super::DbError;
+use diesel::pg::PgConnection;
+use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+use rocket::{
+    http::Status,
+    request::{FromRequest, Outcome, Request},
+    tokio::task::spawn_blocking,
+};
+use std::{
+    env,
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
+
+pub type DbPool = Pool<ConnectionManager<PgConnection>>;
+
+const DEFAULT_POOL_SIZE: u32 = 10;
+const DEFAULT_POOL_TIMEOUT_SECS: u64 = 5;
+
+/// Builds the Postgres pool from `DATABASE_URL`. `DATABASE_POOL_SIZE` and
+/// `DATABASE_POOL_TIMEOUT_SECS` are optional.
+pub fn init_pool() -> DbPool {
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let max_size = env::var("DATABASE_POOL_SIZE")
+        .map(|v| v.parse().expect("DATABASE_POOL_SIZE is not a valid number"))
+        .unwrap_or(DEFAULT_POOL_SIZE);
+    let timeout = env::var("DATABASE_POOL_TIMEOUT_SECS")
+        .map(|v| {
+            v.parse()
+                .expect("DATABASE_POOL_TIMEOUT_SECS is not a valid number")
+        })
+        .unwrap_or(DEFAULT_POOL_TIMEOUT_SECS);
+
+    Pool::builder()
+        .max_size(max_size)
+        .connection_timeout(Duration::from_secs(timeout))
+        .build(ConnectionManager::<PgConnection>::new(&database_url))
+        .unwrap_or_else(|_| panic!("Error connecting to {}", database_url))
+}
+
+/// A pooled connection checked out for the duration of a request.
+pub struct DbConn(pub PooledConnection<ConnectionManager<PgConnection>>);
+
+impl Deref for DbConn {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for DbConn {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for DbConn {
+    type Error = DbError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let pool = match req.rocket().state::<DbPool>() {
+            Some(pool) => pool,
+            None => {
+                let e = DbError::Connection("database pool is not managed".to_string());
+                return Outcome::Error((Status::InternalServerError, e));
+            }
+        };
+
+        match checkout(pool).await {
+            Ok(conn) => Outcome::Success(conn),
+            Err(e) => Outcome::Error((Status::ServiceUnavailable, e)),
+        }
+    }
+}
+
+/// Checks out a connection off the async workers, since it may block for up
+/// to the pool timeout.
+pub async fn checkout(pool: &DbPool) -> Result<DbConn, DbError> {
+    let pool = pool.clone();
+    match spawn_blocking(move || pool.get()).await {
+        Ok(Ok(conn)) => Ok(DbConn(conn)),
+        Ok(Err(e)) => Err(e.into()),
+        Err(e) => Err(DbError::Connection(e.to_string())),
+    }
+}
+
+/// A pool whose checkouts fail quickly, for tests that expect the database to
+/// be down.
+#[cfg(test)]
+pub fn unavailable_pool() -> DbPool {
+    Pool::builder()
+        .min_idle(Some(0))
+        .connection_timeout(Duration::from_millis(100))
+        .build_unchecked(ConnectionManager::<PgConnection>::new(
+            "postgres://127.0.0.1:9/unavailable",
+        ))
+}
@@ -0,0 +1,78 @@
+use chrono::NaiveDate;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use super::{lower, DbError};
+use crate::models::{Employee, NewProject, Onboardees, Projects};
+use crate::schema::{employees, onboardees, projects};
+
+pub fn create_project(
+    conn: &mut PgConnection,
+    name: &str,
+    admin_id: &str,
+) -> Result<Projects, DbError> {
+    let new_project = NewProject {
+        name: name.to_string(),
+        admin_id: admin_id.to_string(),
+    };
+
+    diesel::insert_into(projects::table)
+        .values(&new_project)
+        .returning(Projects::as_returning())
+        .get_result(conn)
+        .map_err(DbError::from)
+}
+
+/// Project names are unique regardless of case.
+pub fn get_project_by_name(conn: &mut PgConnection, name: &str) -> Result<Projects, DbError> {
+    projects::table
+        .filter(lower(projects::name).eq(name.to_lowercase()))
+        .select(Projects::as_select())
+        .first(conn)
+        .map_err(DbError::from)
+}
+
+pub fn add_onboardee(
+    conn: &mut PgConnection,
+    project_id: Uuid,
+    employee_id: &str,
+    onboarding_date: NaiveDate,
+) -> Result<Onboardees, DbError> {
+    let onboardee = Onboardees {
+        project_id,
+        employee_id: employee_id.to_string(),
+        onboarding_date,
+    };
+
+    diesel::insert_into(onboardees::table)
+        .values(&onboardee)
+        .returning(Onboardees::as_returning())
+        .get_result(conn)
+        .map_err(DbError::from)
+}
+
+pub fn get_project_onboardees(
+    conn: &mut PgConnection,
+    project_id: Uuid,
+) -> Result<Vec<(Onboardees, Employee)>, DbError> {
+    onboardees::table
+        .inner_join(employees::table)
+        .filter(onboardees::project_id.eq(project_id))
+        .order(onboardees::onboarding_date.asc())
+        .select((Onboardees::as_select(), Employee::as_select()))
+        .load(conn)
+        .map_err(DbError::from)
+}
+
+pub fn get_onboardee(
+    conn: &mut PgConnection,
+    project_id: Uuid,
+    employee_id: &str,
+) -> Result<Onboardees, DbError> {
+    onboardees::table
+        .find((project_id, employee_id))
+        .select(Onboardees::as_select())
+        .first(conn)
+        .map_err(DbError::from)
+}
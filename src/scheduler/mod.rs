@@ -0,0 +1,189 @@
+#[cfg(test)]
+mod tests;
+
+pub mod store;
+
+use self::store::SharedJobStore;
+use crate::{
+    models::Employee,
+    pg_database::{get_employee, pool::DbPool, DbError},
+    slack_api::SharedSlackApi,
+    utils::response_templates::check_in_template,
+};
+use chrono::{Duration as ChronoDuration, Utc};
+use rocket::tokio::{task::spawn_blocking, time};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const POLL_INTERVAL_SECS: u64 = 30;
+const BATCH_SIZE: usize = 50;
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_DELAY_SECS: i64 = 10 * 60;
+
+/// Check-ins DMed to new employees, counted from their `join_date`.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub enum CheckInStage {
+    #[serde(rename = "day_1")]
+    Day1,
+    #[serde(rename = "week_1")]
+    Week1,
+    #[serde(rename = "day_30")]
+    Day30,
+}
+
+impl CheckInStage {
+    pub const ALL: [CheckInStage; 3] =
+        [CheckInStage::Day1, CheckInStage::Week1, CheckInStage::Day30];
+
+    fn delay(&self) -> ChronoDuration {
+        match self {
+            CheckInStage::Day1 => ChronoDuration::days(1),
+            CheckInStage::Week1 => ChronoDuration::weeks(1),
+            CheckInStage::Day30 => ChronoDuration::days(30),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum JobKind {
+    #[serde(rename = "check_in")]
+    CheckIn {
+        employee_id: String,
+        stage: CheckInStage,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Job {
+    pub kind: JobKind,
+    /// Unix timestamp, in seconds, from which the job may run.
+    pub run_at: i64,
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+/// The check-ins of a new employee, scheduled only the first time they are
+/// stored.
+pub fn check_in_jobs(employee: &Employee) -> Vec<Job> {
+    CheckInStage::ALL
+        .iter()
+        .map(|stage| Job {
+            kind: JobKind::CheckIn {
+                employee_id: employee.id.clone(),
+                stage: *stage,
+            },
+            run_at: (employee.join_date + stage.delay()).timestamp(),
+            attempts: 0,
+        })
+        .collect()
+}
+
+/// The same job a bit later, or `None` once it ran out of attempts.
+pub fn retry(job: &Job, now: i64) -> Option<Job> {
+    if job.attempts + 1 >= MAX_ATTEMPTS {
+        return None;
+    }
+
+    Some(Job {
+        kind: job.kind.clone(),
+        run_at: now + RETRY_DELAY_SECS * (job.attempts as i64 + 1),
+        attempts: job.attempts + 1,
+    })
+}
+
+/// Stores the jobs from a blocking task, so callers answering Slack don't
+/// wait on Redis.
+pub fn spawn_schedule(store: &SharedJobStore, jobs: Vec<Job>) {
+    let store = store.clone();
+    spawn_blocking(move || {
+        for job in jobs {
+            if let Err(e) = store.schedule(&job) {
+                println!("Job {:?} not scheduled: {}", job.kind, e);
+            }
+        }
+    });
+}
+
+/// Polls the store in the background for as long as the process runs.
+pub fn spawn_scheduler(store: SharedJobStore, slack: SharedSlackApi, pool: DbPool) {
+    rocket::tokio::spawn(async move {
+        let mut interval = time::interval(Duration::from_secs(POLL_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            run_due_jobs(&store, &slack, &pool).await;
+        }
+    });
+}
+
+async fn run_due_jobs(store: &SharedJobStore, slack: &SharedSlackApi, pool: &DbPool) {
+    let now = Utc::now().timestamp();
+    let claim_store = store.clone();
+    let jobs = match spawn_blocking(move || claim_store.claim_due(now, BATCH_SIZE)).await {
+        Ok(Ok(jobs)) => jobs,
+        Ok(Err(e)) => {
+            println!("Jobs not claimed: {}", e);
+            return;
+        }
+        Err(e) => {
+            println!("Jobs not claimed: {}", e);
+            return;
+        }
+    };
+
+    for job in jobs {
+        if let Err(e) = run_job(&job, slack, pool).await {
+            println!("Job {:?} failed: {}", job, e);
+            if let Some(next) = retry(&job, now) {
+                let retry_store = store.clone();
+                match spawn_blocking(move || retry_store.schedule(&next)).await {
+                    Ok(Ok(())) => {}
+                    // Left claimed, so the store hands it out again once the
+                    // claim expires.
+                    Ok(Err(e)) => {
+                        println!("Job {:?} not rescheduled: {}", job, e);
+                        continue;
+                    }
+                    Err(e) => {
+                        println!("Job {:?} not rescheduled: {}", job, e);
+                        continue;
+                    }
+                }
+            }
+        }
+
+        let complete_store = store.clone();
+        let done = job.clone();
+        match spawn_blocking(move || complete_store.complete(&done)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => println!("Job {:?} not completed: {}", job, e),
+            Err(e) => println!("Job {:?} not completed: {}", job, e),
+        }
+    }
+}
+
+async fn run_job(job: &Job, slack: &SharedSlackApi, pool: &DbPool) -> Result<(), String> {
+    match &job.kind {
+        JobKind::CheckIn { employee_id, stage } => {
+            let pool = pool.clone();
+            let id = employee_id.clone();
+            let employee = spawn_blocking(move || {
+                pool.get()
+                    .map_err(DbError::from)
+                    .and_then(|mut conn| get_employee(&mut conn, &id))
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+
+            match employee {
+                Ok(employee) if employee.active => slack
+                    .send_dm(employee_id, &check_in_template(*stage, employee_id))
+                    .await
+                    .map_err(|e| e.to_string()),
+                // Nobody left to check in with.
+                Ok(_) | Err(DbError::NotFound) => Ok(()),
+                Err(e) => Err(e.to_string()),
+            }
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use redis::{Client, Commands, Script};
+use std::{fmt, sync::Arc, time::Duration};
+
+use super::Job;
+use crate::utils::load_env::redis_url;
+
+const JOBS_KEY: &str = "onboarding_bot:jobs";
+const CLAIMED_KEY: &str = "onboarding_bot:jobs:claimed";
+const CONNECTION_TIMEOUT_SECS: u64 = 5;
+/// How long a claimed job may run before another poll hands it out again.
+pub const CLAIM_TIMEOUT_SECS: i64 = 10 * 60;
+
+/// Re-queues expired claims, then moves the due jobs to the claimed set with
+/// their claim expiry as score, all in one step so no poller sees a job twice
+/// and none is lost in between.
+const CLAIM_DUE_SCRIPT: &str = r"
+local now = tonumber(ARGV[1])
+for _, member in ipairs(redis.call('ZRANGEBYSCORE', KEYS[2], '-inf', now)) do
+    redis.call('ZREM', KEYS[2], member)
+    redis.call('ZADD', KEYS[1], now, member)
+end
+local due = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', now, 'LIMIT', 0, tonumber(ARGV[2]))
+for _, member in ipairs(due) do
+    redis.call('ZREM', KEYS[1], member)
+    redis.call('ZADD', KEYS[2], now + tonumber(ARGV[3]), member)
+end
+return due
+";
+
+#[derive(Debug, PartialEq)]
+pub enum JobStoreError {
+    Connection(String),
+    Encoding(String),
+}
+
+impl fmt::Display for JobStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JobStoreError::Connection(detail) => write!(f, "connection failure: {}", detail),
+            JobStoreError::Encoding(detail) => write!(f, "invalid job: {}", detail),
+        }
+    }
+}
+
+impl std::error::Error for JobStoreError {}
+
+impl From<redis::RedisError> for JobStoreError {
+    fn from(e: redis::RedisError) -> Self {
+        JobStoreError::Connection(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for JobStoreError {
+    fn from(e: serde_json::Error) -> Self {
+        JobStoreError::Encoding(e.to_string())
+    }
+}
+
+/// Where pending jobs wait until their `run_at`.
+pub trait JobStore: Send + Sync {
+    /// Scheduling the exact same job twice keeps a single copy.
+    fn schedule(&self, job: &Job) -> Result<(), JobStoreError>;
+
+    /// Claims and returns up to `limit` jobs due at `now`. A job is handed to
+    /// a single caller, even with several bot instances polling, until
+    /// `CLAIM_TIMEOUT_SECS` pass without it being completed.
+    fn claim_due(&self, now: i64, limit: usize) -> Result<Vec<Job>, JobStoreError>;
+
+    /// Forgets a claimed job once it ran or its retry was scheduled.
+    fn complete(&self, job: &Job) -> Result<(), JobStoreError>;
+}
+
+/// Shared handle kept in Rocket state and in the scheduler task.
+pub type SharedJobStore = Arc<dyn JobStore>;
+
+/// Jobs kept in a Redis sorted set scored by `run_at`, so they survive
+/// restarts of the bot. Claimed jobs wait in a second set until completed, so
+/// a crash mid-run only delays them.
+pub struct RedisJobStore {
+    client: Client,
+}
+
+impl RedisJobStore {
+    pub fn from_env() -> Self {
+        RedisJobStore {
+            client: Client::open(redis_url()).expect("Invalid Redis URL"),
+        }
+    }
+
+    fn connection(&self) -> Result<redis::Connection, JobStoreError> {
+        self.client
+            .get_connection_with_timeout(Duration::from_secs(CONNECTION_TIMEOUT_SECS))
+            .map_err(JobStoreError::from)
+    }
+}
+
+impl JobStore for RedisJobStore {
+    fn schedule(&self, job: &Job) -> Result<(), JobStoreError> {
+        let member = serde_json::to_string(job)?;
+        self.connection()?
+            .zadd::<_, _, _, ()>(JOBS_KEY, member, job.run_at)
+            .map_err(JobStoreError::from)
+    }
+
+    fn claim_due(&self, now: i64, limit: usize) -> Result<Vec<Job>, JobStoreError> {
+        let mut conn = self.connection()?;
+        let members: Vec<String> = Script::new(CLAIM_DUE_SCRIPT)
+            .key(JOBS_KEY)
+            .key(CLAIMED_KEY)
+            .arg(now)
+            .arg(limit)
+            .arg(CLAIM_TIMEOUT_SECS)
+            .invoke(&mut conn)?;
+
+        let mut jobs = vec![];
+        for member in members {
+            match serde_json::from_str(&member) {
+                Ok(job) => jobs.push(job),
+                Err(e) => {
+                    println!("Dropped unreadable job {}: {}", member, e);
+                    conn.zrem::<_, _, ()>(CLAIMED_KEY, &member)?;
+                }
+            }
+        }
+        Ok(jobs)
+    }
+
+    fn complete(&self, job: &Job) -> Result<(), JobStoreError> {
+        let member = serde_json::to_string(job)?;
+        self.connection()?
+            .zrem::<_, _, ()>(CLAIMED_KEY, member)
+            .map_err(JobStoreError::from)
+    }
+}
+
+/// In-process `JobStore` for tests.
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemoryJobStore {
+    jobs: std::sync::Mutex<Vec<Job>>,
+    /// Claimed jobs with the time their claim expires.
+    claimed: std::sync::Mutex<Vec<(Job, i64)>>,
+}
+
+#[cfg(test)]
+impl MemoryJobStore {
+    pub fn jobs(&self) -> Vec<Job> {
+        self.jobs.lock().unwrap().clone()
+    }
+
+    pub fn claimed(&self) -> Vec<Job> {
+        let claimed = self.claimed.lock().unwrap();
+        claimed.iter().map(|(job, _)| job.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+impl JobStore for MemoryJobStore {
+    fn schedule(&self, job: &Job) -> Result<(), JobStoreError> {
+        let mut jobs = self.jobs.lock().unwrap();
+        if !jobs.contains(job) {
+            jobs.push(job.clone());
+        }
+        Ok(())
+    }
+
+    fn claim_due(&self, now: i64, limit: usize) -> Result<Vec<Job>, JobStoreError> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let mut claimed = self.claimed.lock().unwrap();
+        claimed.retain(|(job, expires_at)| {
+            if *expires_at <= now && !jobs.contains(job) {
+                jobs.push(job.clone());
+            }
+            *expires_at > now
+        });
+        jobs.sort_by_key(|job| job.run_at);
+
+        let due = jobs
+            .iter()
+            .take_while(|job| job.run_at <= now)
+            .take(limit)
+            .count();
+        let due: Vec<Job> = jobs.drain(..due).collect();
+        claimed.extend(
+            due.iter()
+                .map(|job| (job.clone(), now + CLAIM_TIMEOUT_SECS)),
+        );
+        Ok(due)
+    }
+
+    fn complete(&self, job: &Job) -> Result<(), JobStoreError> {
+        self.claimed
+            .lock()
+            .unwrap()
+            .retain(|(claimed, _)| claimed != job);
+        Ok(())
+    }
+}
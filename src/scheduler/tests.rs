@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod test_jobs {
+    use crate::models::test_employee;
+    use crate::scheduler::{check_in_jobs, retry, CheckInStage, Job, JobKind};
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    fn check_in(stage: CheckInStage, run_at: i64, attempts: u32) -> Job {
+        Job {
+            kind: JobKind::CheckIn {
+                employee_id: "ABC123".to_string(),
+                stage,
+            },
+            run_at,
+            attempts,
+        }
+    }
+
+    #[test]
+    fn should_schedule_check_ins_from_join_date() {
+        let joined = 1706745600; // 2024-02-01 00:00:00 UTC-0
+
+        let jobs = check_in_jobs(&test_employee("ABC123", joined));
+
+        assert_eq!(
+            jobs,
+            vec![
+                check_in(CheckInStage::Day1, joined + DAY, 0),
+                check_in(CheckInStage::Week1, joined + 7 * DAY, 0),
+                check_in(CheckInStage::Day30, joined + 30 * DAY, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_retry_later_until_out_of_attempts() {
+        let now = 1706745600;
+        let job = check_in(CheckInStage::Day1, now, 0);
+
+        let first = retry(&job, now).unwrap();
+        let second = retry(&first, now).unwrap();
+
+        assert_eq!(first, check_in(CheckInStage::Day1, now + 600, 1));
+        assert_eq!(second, check_in(CheckInStage::Day1, now + 1200, 2));
+        assert_eq!(retry(&second, now), None);
+    }
+
+    #[test]
+    fn should_serialize_jobs_stably() {
+        let job = check_in(CheckInStage::Week1, 1707350400, 0);
+
+        let json = serde_json::to_string(&job).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"kind":{"type":"check_in","employee_id":"ABC123","stage":"week_1"},"run_at":1707350400,"attempts":0}"#
+        );
+        assert_eq!(serde_json::from_str::<Job>(&json).unwrap(), job);
+    }
+}
+
+#[cfg(test)]
+mod test_memory_job_store {
+    use crate::scheduler::store::{JobStore, MemoryJobStore, CLAIM_TIMEOUT_SECS};
+    use crate::scheduler::{CheckInStage, Job, JobKind};
+
+    fn job(employee_id: &str, run_at: i64) -> Job {
+        Job {
+            kind: JobKind::CheckIn {
+                employee_id: employee_id.to_string(),
+                stage: CheckInStage::Day1,
+            },
+            run_at,
+            attempts: 0,
+        }
+    }
+
+    #[test]
+    fn should_only_claim_due_jobs_once() {
+        let store = MemoryJobStore::default();
+        for j in [job("C", 30), job("A", 10), job("B", 20), job("A", 10)] {
+            store.schedule(&j).unwrap();
+        }
+
+        assert_eq!(
+            store.claim_due(20, 10).unwrap(),
+            vec![job("A", 10), job("B", 20)]
+        );
+        assert_eq!(store.claim_due(20, 10).unwrap(), vec![]);
+        assert_eq!(store.jobs(), vec![job("C", 30)]);
+    }
+
+    #[test]
+    fn should_claim_at_most_limit_jobs() {
+        let store = MemoryJobStore::default();
+        for j in [job("A", 10), job("B", 20)] {
+            store.schedule(&j).unwrap();
+        }
+
+        assert_eq!(store.claim_due(20, 1).unwrap(), vec![job("A", 10)]);
+    }
+
+    #[test]
+    fn should_hand_out_unfinished_jobs_again_once_claim_expires() {
+        let store = MemoryJobStore::default();
+        store.schedule(&job("A", 10)).unwrap();
+
+        assert_eq!(store.claim_due(10, 10).unwrap(), vec![job("A", 10)]);
+        assert_eq!(store.claimed(), vec![job("A", 10)]);
+        assert_eq!(
+            store.claim_due(10 + CLAIM_TIMEOUT_SECS - 1, 10).unwrap(),
+            vec![]
+        );
+        assert_eq!(
+            store.claim_due(10 + CLAIM_TIMEOUT_SECS, 10).unwrap(),
+            vec![job("A", 10)]
+        );
+    }
+
+    #[test]
+    fn should_not_hand_out_completed_jobs_again() {
+        let store = MemoryJobStore::default();
+        store.schedule(&job("A", 10)).unwrap();
+
+        let claimed = store.claim_due(10, 10).unwrap();
+        store.complete(&claimed[0]).unwrap();
+
+        assert_eq!(store.claimed(), vec![]);
+        assert_eq!(
+            store.claim_due(10 + CLAIM_TIMEOUT_SECS, 10).unwrap(),
+            vec![]
+        );
+    }
+}
@@ -1,12 +1,42 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    announcement_settings (id) {
+        id -> Int4,
+        enabled -> Bool,
+        channel_id -> Nullable<Varchar>,
+        template -> Varchar,
+        intro -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    buddies (employee_id) {
+        employee_id -> Varchar,
+        buddy_id -> Varchar,
+        assigned_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    checklist_tasks (id) {
+        id -> Uuid,
+        project_id -> Uuid,
+        title -> Varchar,
+        position -> Int4,
+    }
+}
+
 diesel::table! {
     employees (id) {
         id -> Varchar,
-        email -> Varchar,
+        email -> Nullable<Varchar>,
         full_name -> Varchar,
         country -> Nullable<Varchar>,
-        join_date -> Timestamp,
+        join_date -> Timestamptz,
+        active -> Bool,
+        left_date -> Nullable<Date>,
+        left_by_slack -> Bool,
     }
 }
 
@@ -14,7 +44,7 @@ diesel::table! {
     onboardees (project_id, employee_id) {
         project_id -> Uuid,
         employee_id -> Varchar,
-        onboarding_date -> Timestamp,
+        onboarding_date -> Date,
     }
 }
 
@@ -26,8 +56,28 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    task_completions (task_id, employee_id) {
+        task_id -> Uuid,
+        employee_id -> Varchar,
+        completed_by -> Varchar,
+        completed_at -> Timestamptz,
+    }
+}
+
+diesel::joinable!(checklist_tasks -> projects (project_id));
 diesel::joinable!(onboardees -> employees (employee_id));
 diesel::joinable!(onboardees -> projects (project_id));
 diesel::joinable!(projects -> employees (admin_id));
+diesel::joinable!(task_completions -> checklist_tasks (task_id));
+diesel::joinable!(task_completions -> employees (employee_id));
 
-diesel::allow_tables_to_appear_in_same_query!(employees, onboardees, projects,);
+diesel::allow_tables_to_appear_in_same_query!(
+    announcement_settings,
+    buddies,
+    checklist_tasks,
+    employees,
+    onboardees,
+    projects,
+    task_completions,
+);
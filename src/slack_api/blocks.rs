@@ -0,0 +1,129 @@
+use serde::Serialize;
+
+/// The Block Kit text objects the bot renders.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum Text {
+    #[serde(rename = "plain_text")]
+    Plain { text: String, emoji: bool },
+    #[serde(rename = "mrkdwn")]
+    Mrkdwn { text: String },
+}
+
+impl Text {
+    pub fn plain(text: impl Into<String>) -> Text {
+        Text::Plain {
+            text: text.into(),
+            emoji: true,
+        }
+    }
+
+    pub fn mrkdwn(text: impl Into<String>) -> Text {
+        Text::Mrkdwn { text: text.into() }
+    }
+}
+
+/// Interactive elements, handled by the interactivity endpoint.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum Element {
+    #[serde(rename = "button")]
+    Button {
+        text: Text,
+        action_id: String,
+        /// Sent back as is when the button is clicked.
+        value: String,
+    },
+    #[serde(rename = "datepicker")]
+    Datepicker {
+        action_id: String,
+        /// `YYYY-MM-DD`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        initial_date: Option<String>,
+    },
+    #[serde(rename = "plain_text_input")]
+    PlainTextInput {
+        action_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        placeholder: Option<Text>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        initial_value: Option<String>,
+    },
+}
+
+impl Element {
+    pub fn datepicker(action_id: &str, initial_date: Option<String>) -> Element {
+        Element::Datepicker {
+            action_id: action_id.to_string(),
+            initial_date,
+        }
+    }
+
+    pub fn button(text: &str, action_id: &str, value: String) -> Element {
+        Element::Button {
+            text: Text::plain(text),
+            action_id: action_id.to_string(),
+            value,
+        }
+    }
+}
+
+/// The Block Kit layout blocks the bot renders.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum Block {
+    #[serde(rename = "header")]
+    Header { text: Text },
+    #[serde(rename = "section")]
+    Section { text: Text },
+    /// Small, grey text under a section.
+    #[serde(rename = "context")]
+    Context { elements: Vec<Text> },
+    #[serde(rename = "divider")]
+    Divider,
+    #[serde(rename = "actions")]
+    Actions { elements: Vec<Element> },
+    /// Form fields, only valid in modals.
+    #[serde(rename = "input")]
+    Input {
+        block_id: String,
+        label: Text,
+        element: Element,
+        optional: bool,
+    },
+}
+
+impl Block {
+    /// Headers only take plain text.
+    pub fn header(text: impl Into<String>) -> Block {
+        Block::Header {
+            text: Text::plain(text),
+        }
+    }
+
+    pub fn section(text: impl Into<String>) -> Block {
+        Block::Section {
+            text: Text::mrkdwn(text),
+        }
+    }
+
+    pub fn context(elements: Vec<String>) -> Block {
+        Block::Context {
+            elements: elements.into_iter().map(Text::mrkdwn).collect(),
+        }
+    }
+}
+
+/// A modal opened with `views.open`. Its submission reaches the interactivity
+/// endpoint as a `view_submission` with the same `callback_id`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename = "modal")]
+pub struct Modal {
+    pub callback_id: String,
+    pub title: Text,
+    pub submit: Text,
+    pub close: Text,
+    /// Sent back with the submission, up to 3000 characters.
+    pub private_metadata: String,
+    pub blocks: Vec<Block>,
+}
@@ -0,0 +1,325 @@
+#[cfg(test)]
+mod tests;
+
+pub mod blocks;
+
+use self::blocks::{Block, Modal};
+use chrono_tz::Tz;
+use reqwest::{Client, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{env, fmt, sync::Arc, time::Duration};
+
+const DEFAULT_API_URL: &str = "https://slack.com/api";
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+/// Lookups like `users.info` come before opening a modal, whose `trigger_id`
+/// expires 3 seconds after the command.
+const LOOKUP_TIMEOUT_SECS: u64 = 2;
+
+#[derive(Debug, PartialEq)]
+pub enum SlackApiError {
+    /// The request never got a response.
+    Http(String),
+    /// Slack answered with `ok: false` and this error code.
+    Api(String),
+    /// The response body wasn't what the method documents.
+    Parse(String),
+}
+
+impl fmt::Display for SlackApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SlackApiError::Http(detail) => write!(f, "http failure: {}", detail),
+            SlackApiError::Api(code) => write!(f, "slack error: {}", code),
+            SlackApiError::Parse(detail) => write!(f, "unexpected response: {}", detail),
+        }
+    }
+}
+
+impl std::error::Error for SlackApiError {}
+
+/// Who sees a reply posted to a slash command's `response_url`.
+#[derive(Debug, Serialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseType {
+    /// Only the person who ran the command.
+    #[default]
+    Ephemeral,
+    /// Everyone in the channel.
+    InChannel,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SlashResponse {
+    pub response_type: ResponseType,
+    /// Shown by notifications and by clients that can't render `blocks`.
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocks: Option<Vec<Block>>,
+    /// Replaces the message the `response_url` came from, e.g. to turn a page.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub replace_original: bool,
+}
+
+/// The Slack Web API methods the bot calls.
+#[rocket::async_trait]
+pub trait SlackApi: Send + Sync {
+    /// Opens (or reuses) the DM with `user_id` and returns its channel id.
+    async fn open_conversation(&self, user_id: &str) -> Result<String, SlackApiError>;
+
+    async fn post_message(&self, channel: &str, text: &str) -> Result<(), SlackApiError>;
+
+    /// The timezone `user_id` set in their Slack profile.
+    async fn user_timezone(&self, user_id: &str) -> Result<Tz, SlackApiError>;
+
+    /// Opens `modal` for whoever triggered `trigger_id`, within 3 seconds.
+    async fn open_view(&self, trigger_id: &str, modal: &Modal) -> Result<(), SlackApiError>;
+
+    /// Answers a slash command after its 3 seconds are over.
+    async fn respond(
+        &self,
+        response_url: &str,
+        response: &SlashResponse,
+    ) -> Result<(), SlackApiError>;
+
+    async fn send_dm(&self, user_id: &str, text: &str) -> Result<(), SlackApiError> {
+        let channel = self.open_conversation(user_id).await?;
+        self.post_message(&channel, text).await
+    }
+}
+
+/// Shared handle kept in Rocket state, so background tasks can hold on to it.
+pub type SharedSlackApi = Arc<dyn SlackApi>;
+
+/// Sends `(user_id, text)` DMs in the background, so the caller can answer
+/// Slack right away. Failures are only logged.
+pub fn spawn_dms(slack: &SharedSlackApi, dms: Vec<(String, String)>) {
+    let slack = slack.clone();
+    rocket::tokio::spawn(async move {
+        for (user_id, text) in dms {
+            if let Err(e) = slack.send_dm(&user_id, &text).await {
+                println!("DM to {} not sent: {}", user_id, e);
+            }
+        }
+    });
+}
+
+#[derive(Deserialize)]
+struct SlackResponse {
+    ok: bool,
+    error: Option<String>,
+    #[serde(flatten)]
+    body: Value,
+}
+
+/// `SlackApi` over HTTP, authenticated with a bot token.
+pub struct SlackClient {
+    http: Client,
+    base_url: String,
+    token: String,
+}
+
+impl SlackClient {
+    pub fn new(token: &str, base_url: &str) -> Self {
+        SlackClient {
+            http: Client::builder()
+                .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+                .build()
+                .expect("HTTP client not built"),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    /// Reads `SLACK_BOT_TOKEN`. `SLACK_API_URL` is optional.
+    pub fn from_env() -> Self {
+        let token = env::var("SLACK_BOT_TOKEN").expect("SLACK_BOT_TOKEN must be set");
+        let base_url = env::var("SLACK_API_URL").unwrap_or(DEFAULT_API_URL.to_string());
+        SlackClient::new(&token, &base_url)
+    }
+
+    async fn call(&self, method: &str, payload: Value) -> Result<Value, SlackApiError> {
+        let request = self
+            .http
+            .post(format!("{}/{}", self.base_url, method))
+            .json(&payload);
+        self.send(request).await
+    }
+
+    /// Read methods like `users.info` don't take JSON bodies.
+    async fn call_get(&self, method: &str, query: &[(&str, &str)]) -> Result<Value, SlackApiError> {
+        let request = self
+            .http
+            .get(format!("{}/{}", self.base_url, method))
+            .query(query)
+            .timeout(Duration::from_secs(LOOKUP_TIMEOUT_SECS));
+        self.send(request).await
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Value, SlackApiError> {
+        let response = request
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .map_err(|e| SlackApiError::Http(e.to_string()))?;
+
+        let response: SlackResponse = response
+            .json()
+            .await
+            .map_err(|e| SlackApiError::Parse(e.to_string()))?;
+
+        if response.ok {
+            Ok(response.body)
+        } else {
+            Err(SlackApiError::Api(
+                response.error.unwrap_or("unknown_error".to_string()),
+            ))
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl SlackApi for SlackClient {
+    async fn open_conversation(&self, user_id: &str) -> Result<String, SlackApiError> {
+        let body = self
+            .call("conversations.open", json!({ "users": user_id }))
+            .await?;
+
+        body["channel"]["id"]
+            .as_str()
+            .map(String::from)
+            .ok_or(SlackApiError::Parse("missing channel.id".to_string()))
+    }
+
+    async fn post_message(&self, channel: &str, text: &str) -> Result<(), SlackApiError> {
+        self.call(
+            "chat.postMessage",
+            json!({ "channel": channel, "text": text }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn user_timezone(&self, user_id: &str) -> Result<Tz, SlackApiError> {
+        let body = self.call_get("users.info", &[("user", user_id)]).await?;
+        let tz = body["user"]["tz"]
+            .as_str()
+            .ok_or(SlackApiError::Parse("missing user.tz".to_string()))?;
+        tz.parse()
+            .map_err(|_| SlackApiError::Parse(format!("unknown timezone {}", tz)))
+    }
+
+    async fn open_view(&self, trigger_id: &str, modal: &Modal) -> Result<(), SlackApiError> {
+        self.call(
+            "views.open",
+            json!({ "trigger_id": trigger_id, "view": modal }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn respond(
+        &self,
+        response_url: &str,
+        response: &SlashResponse,
+    ) -> Result<(), SlackApiError> {
+        // `response_url`s carry their own credentials and answer plain text.
+        let status = self
+            .http
+            .post(response_url)
+            .json(response)
+            .send()
+            .await
+            .map_err(|e| SlackApiError::Http(e.to_string()))?
+            .status();
+
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(SlackApiError::Http(format!(
+                "response_url answered {}",
+                status
+            )))
+        }
+    }
+}
+
+/// What `ChannelSlackApi` was asked to do.
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlackCall {
+    PostMessage {
+        channel: String,
+        text: String,
+    },
+    OpenView {
+        trigger_id: String,
+        modal: Modal,
+    },
+    Respond {
+        response_url: String,
+        response: SlashResponse,
+    },
+}
+
+/// `SlackApi` for tests that sends every call down a channel, so tests can
+/// wait for what background tasks did. Users are in `timezone`, if any.
+#[cfg(test)]
+pub struct ChannelSlackApi {
+    calls: std::sync::mpsc::Sender<SlackCall>,
+    timezone: Option<Tz>,
+}
+
+#[cfg(test)]
+impl ChannelSlackApi {
+    pub fn new(timezone: Option<Tz>) -> (Self, std::sync::mpsc::Receiver<SlackCall>) {
+        let (calls, received) = std::sync::mpsc::channel();
+        (ChannelSlackApi { calls, timezone }, received)
+    }
+
+    fn record(&self, call: SlackCall) {
+        // Tests that don't care about the calls may drop the receiver.
+        let _ = self.calls.send(call);
+    }
+}
+
+#[cfg(test)]
+#[rocket::async_trait]
+impl SlackApi for ChannelSlackApi {
+    async fn open_conversation(&self, user_id: &str) -> Result<String, SlackApiError> {
+        Ok(format!("D{}", user_id))
+    }
+
+    async fn post_message(&self, channel: &str, text: &str) -> Result<(), SlackApiError> {
+        self.record(SlackCall::PostMessage {
+            channel: channel.to_string(),
+            text: text.to_string(),
+        });
+        Ok(())
+    }
+
+    async fn user_timezone(&self, _user_id: &str) -> Result<Tz, SlackApiError> {
+        self.timezone
+            .ok_or(SlackApiError::Api("user_not_found".to_string()))
+    }
+
+    async fn open_view(&self, trigger_id: &str, modal: &Modal) -> Result<(), SlackApiError> {
+        self.record(SlackCall::OpenView {
+            trigger_id: trigger_id.to_string(),
+            modal: modal.clone(),
+        });
+        Ok(())
+    }
+
+    async fn respond(
+        &self,
+        response_url: &str,
+        response: &SlashResponse,
+    ) -> Result<(), SlackApiError> {
+        self.record(SlackCall::Respond {
+            response_url: response_url.to_string(),
+            response: response.clone(),
+        });
+        Ok(())
+    }
+}
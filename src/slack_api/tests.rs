@@ -0,0 +1,216 @@
+#[cfg(test)]
+mod test_slack_client {
+    use mockito::{Matcher, Server};
+    use serde_json::json;
+
+    use crate::slack_api::{
+        blocks::{Modal, Text},
+        ResponseType, SlackApi, SlackApiError, SlackClient, SlashResponse,
+    };
+
+    const TOKEN: &str = "xoxb-test";
+
+    #[rocket::async_test]
+    async fn should_open_dm_and_post_message() {
+        let mut server = Server::new_async().await;
+        let open = server
+            .mock("POST", "/conversations.open")
+            .match_header("authorization", "Bearer xoxb-test")
+            .match_body(Matcher::Json(json!({ "users": "U123" })))
+            .with_body(r#"{"ok":true,"channel":{"id":"D123"}}"#)
+            .create_async()
+            .await;
+        let post = server
+            .mock("POST", "/chat.postMessage")
+            .match_header("authorization", "Bearer xoxb-test")
+            .match_body(Matcher::Json(json!({ "channel": "D123", "text": "hola" })))
+            .with_body(r#"{"ok":true,"ts":"1503435956.000247"}"#)
+            .create_async()
+            .await;
+
+        let client = SlackClient::new(TOKEN, &server.url());
+        let result = client.send_dm("U123", "hola").await;
+
+        assert_eq!(result, Ok(()));
+        open.assert_async().await;
+        post.assert_async().await;
+    }
+
+    #[rocket::async_test]
+    async fn should_surface_slack_error_codes() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("POST", "/chat.postMessage")
+            .with_body(r#"{"ok":false,"error":"channel_not_found"}"#)
+            .create_async()
+            .await;
+
+        let client = SlackClient::new(TOKEN, &server.url());
+        let result = client.post_message("C404", "hola").await;
+
+        assert_eq!(
+            result,
+            Err(SlackApiError::Api("channel_not_found".to_string()))
+        );
+    }
+
+    #[rocket::async_test]
+    async fn should_err_when_channel_is_missing() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("POST", "/conversations.open")
+            .with_body(r#"{"ok":true}"#)
+            .create_async()
+            .await;
+
+        let client = SlackClient::new(TOKEN, &server.url());
+        let result = client.open_conversation("U123").await;
+
+        assert!(matches!(result, Err(SlackApiError::Parse(_))));
+    }
+
+    #[rocket::async_test]
+    async fn should_read_user_timezones() {
+        let mut server = Server::new_async().await;
+        let info = server
+            .mock("GET", "/users.info")
+            .match_header("authorization", "Bearer xoxb-test")
+            .match_query(Matcher::UrlEncoded("user".to_string(), "U123".to_string()))
+            .with_body(r#"{"ok":true,"user":{"id":"U123","tz":"America/Argentina/Buenos_Aires"}}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/users.info")
+            .match_query(Matcher::UrlEncoded("user".to_string(), "U404".to_string()))
+            .with_body(r#"{"ok":true,"user":{"id":"U404","tz":"Mars/Olympus_Mons"}}"#)
+            .create_async()
+            .await;
+
+        let client = SlackClient::new(TOKEN, &server.url());
+
+        assert_eq!(
+            client.user_timezone("U123").await,
+            Ok(chrono_tz::America::Argentina::Buenos_Aires)
+        );
+        assert!(matches!(
+            client.user_timezone("U404").await,
+            Err(SlackApiError::Parse(_))
+        ));
+        info.assert_async().await;
+    }
+
+    #[rocket::async_test]
+    async fn should_give_up_on_slow_lookups() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/users.info")
+            .match_query(Matcher::Any)
+            .with_body_from_request(|_| {
+                std::thread::sleep(std::time::Duration::from_secs(3));
+                br#"{"ok":true,"user":{"id":"U123","tz":"America/Santiago"}}"#.to_vec()
+            })
+            .create_async()
+            .await;
+
+        let client = SlackClient::new(TOKEN, &server.url());
+
+        // In time for the modal the lookup comes before.
+        assert!(matches!(
+            client.user_timezone("U123").await,
+            Err(SlackApiError::Http(_))
+        ));
+    }
+
+    #[rocket::async_test]
+    async fn should_open_modals_with_trigger_id() {
+        let mut server = Server::new_async().await;
+        let open = server
+            .mock("POST", "/views.open")
+            .match_header("authorization", "Bearer xoxb-test")
+            .match_body(Matcher::PartialJson(json!({
+                "trigger_id": "1.2.abc",
+                "view": { "type": "modal", "callback_id": "rango", "private_metadata": "{}" }
+            })))
+            .with_body(r#"{"ok":true,"view":{"id":"V1"}}"#)
+            .create_async()
+            .await;
+
+        let client = SlackClient::new(TOKEN, &server.url());
+        let modal = Modal {
+            callback_id: "rango".to_string(),
+            title: Text::plain("Nuevos"),
+            submit: Text::plain("Buscar"),
+            close: Text::plain("Cancelar"),
+            private_metadata: "{}".to_string(),
+            blocks: vec![],
+        };
+        let result = client.open_view("1.2.abc", &modal).await;
+
+        assert_eq!(result, Ok(()));
+        open.assert_async().await;
+    }
+
+    #[rocket::async_test]
+    async fn should_post_responses_to_response_url_without_token() {
+        let mut server = Server::new_async().await;
+        let respond = server
+            .mock("POST", "/commands/T1/2/3")
+            .match_header("authorization", Matcher::Missing)
+            .match_body(Matcher::Json(
+                json!({ "response_type": "in_channel", "text": "hola" }),
+            ))
+            .with_body("ok")
+            .create_async()
+            .await;
+
+        let client = SlackClient::new(TOKEN, "http://127.0.0.1:9");
+        let response = SlashResponse {
+            response_type: ResponseType::InChannel,
+            text: "hola".to_string(),
+            blocks: None,
+            replace_original: false,
+        };
+        let result = client
+            .respond(&format!("{}/commands/T1/2/3", server.url()), &response)
+            .await;
+
+        assert_eq!(result, Ok(()));
+        respond.assert_async().await;
+    }
+}
+
+#[cfg(test)]
+mod test_blocks {
+    use serde_json::json;
+
+    use crate::slack_api::{blocks::Block, ResponseType, SlashResponse};
+
+    #[test]
+    fn should_serialize_blocks_with_text_fallback() {
+        let response = SlashResponse {
+            response_type: ResponseType::Ephemeral,
+            text: "Febrero 2024: <@U123>".to_string(),
+            blocks: Some(vec![
+                Block::header("Febrero 2024"),
+                Block::section("<@U123>"),
+                Block::context(vec!["Argentina".to_string()]),
+                Block::Divider,
+            ]),
+            replace_original: false,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({
+                "response_type": "ephemeral",
+                "text": "Febrero 2024: <@U123>",
+                "blocks": [
+                    { "type": "header", "text": { "type": "plain_text", "text": "Febrero 2024", "emoji": true } },
+                    { "type": "section", "text": { "type": "mrkdwn", "text": "<@U123>" } },
+                    { "type": "context", "elements": [{ "type": "mrkdwn", "text": "Argentina" }] },
+                    { "type": "divider" }
+                ]
+            })
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use super::{db_error_message, is_admin, SlashCommand};
+use crate::authenticate::SignedForm;
+use crate::models::AnnouncementSettingsChange;
+use crate::pg_database::{
+    announcements::{get_announcement_settings, update_announcement_settings},
+    pool::DbConn,
+    DbError,
+};
+use crate::utils::{
+    parse_channel::parse_channel, response_templates::announcement_settings_template,
+};
+
+use rocket::{http::Status, response::status};
+
+pub const USAGE: &str = "Uso:\n\
+    - `/anuncios ver`\n\
+    - `/anuncios activar|desactivar`\n\
+    - `/anuncios canal #canal`\n\
+    - `/anuncios mensaje <texto>` (podés usar `{usuario}` y `{pais}`)\n\
+    - `/anuncios intro [texto]` (sin texto la borra)";
+
+#[derive(Debug, PartialEq)]
+pub enum AnnouncementCommand {
+    Show,
+    Enable,
+    Disable,
+    Channel(String),
+    Template(String),
+    Intro(Option<String>),
+}
+
+/// Parses `<subcomando> [valor]`. The value keeps its spaces, since templates
+/// and intros are free text.
+pub fn parse_announcement_command(text: &str) -> Option<AnnouncementCommand> {
+    let text = text.trim();
+    let (subcommand, value) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let value = value.trim();
+
+    match (subcommand.to_lowercase().as_str(), value) {
+        ("" | "ver", "") => Some(AnnouncementCommand::Show),
+        ("activar", "") => Some(AnnouncementCommand::Enable),
+        ("desactivar", "") => Some(AnnouncementCommand::Disable),
+        ("canal", channel) => {
+            parse_channel(channel).map(|id| AnnouncementCommand::Channel(id.to_string()))
+        }
+        ("mensaje", "") => None,
+        ("mensaje", template) => Some(AnnouncementCommand::Template(template.to_string())),
+        ("intro", "") => Some(AnnouncementCommand::Intro(None)),
+        ("intro", intro) => Some(AnnouncementCommand::Intro(Some(intro.to_string()))),
+        _ => None,
+    }
+}
+
+impl AnnouncementCommand {
+    fn into_change(self) -> Option<AnnouncementSettingsChange> {
+        let change = match self {
+            AnnouncementCommand::Show => return None,
+            AnnouncementCommand::Enable => AnnouncementSettingsChange {
+                enabled: Some(true),
+                ..Default::default()
+            },
+            AnnouncementCommand::Disable => AnnouncementSettingsChange {
+                enabled: Some(false),
+                ..Default::default()
+            },
+            AnnouncementCommand::Channel(id) => AnnouncementSettingsChange {
+                channel_id: Some(Some(id)),
+                ..Default::default()
+            },
+            AnnouncementCommand::Template(template) => AnnouncementSettingsChange {
+                template: Some(template),
+                ..Default::default()
+            },
+            AnnouncementCommand::Intro(intro) => AnnouncementSettingsChange {
+                intro: Some(intro),
+                ..Default::default()
+            },
+        };
+        Some(change)
+    }
+}
+
+#[post(
+    "/command/anuncios",
+    data = "<command>",
+    format = "application/x-www-form-urlencoded"
+)]
+pub fn announcements_command_route(
+    conn: Result<DbConn, DbError>,
+    command: SignedForm<SlashCommand>,
+) -> status::Custom<String> {
+    let command = command.into_inner();
+    println!("slash command: {} {}", command.command, command.text);
+
+    if !is_admin(&command.user_id) {
+        return status::Custom(
+            Status::Ok,
+            "Sólo los admins pueden configurar los anuncios.".to_string(),
+        );
+    }
+
+    let announcement_command = match parse_announcement_command(&command.text) {
+        Some(announcement_command) => announcement_command,
+        None => return status::Custom(Status::Ok, USAGE.to_string()),
+    };
+
+    let settings = conn.and_then(|mut conn| match announcement_command.into_change() {
+        Some(change) => update_announcement_settings(&mut conn, &change),
+        None => get_announcement_settings(&mut conn),
+    });
+
+    match settings {
+        Ok(settings) => status::Custom(Status::Ok, announcement_settings_template(&settings)),
+        Err(e) => status::Custom(Status::Ok, db_error_message(e)),
+    }
+}
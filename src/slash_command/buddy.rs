@@ -0,0 +1,126 @@
+use super::{db_error_message, is_admin, SlashCommand};
+use crate::authenticate::SignedForm;
+use crate::pg_database::{
+    buddies::{assign_buddy, get_buddy, get_buddy_mentees},
+    get_employee,
+    pool::DbConn,
+    DbError,
+};
+use crate::slack_api::{spawn_dms, SharedSlackApi};
+use crate::utils::{
+    parse_mention::parse_mention,
+    response_templates::{
+        buddies_template, buddy_assigned_dm_template, buddy_assignment_template,
+        employee_not_found_template, your_buddy_dm_template,
+    },
+};
+
+use chrono::Utc;
+use diesel::pg::PgConnection;
+use rocket::{http::Status, response::status, State};
+
+pub const USAGE: &str = "Uso:\n\
+    - `/buddy [@persona]` para ver buddies\n\
+    - `/buddy @persona @buddy` para asignar uno (sólo admins)";
+
+#[derive(Debug, PartialEq)]
+pub enum BuddyCommand {
+    Show {
+        employee_id: Option<String>,
+    },
+    Assign {
+        employee_id: String,
+        buddy_id: String,
+    },
+}
+
+pub fn parse_buddy_command(text: &str) -> Option<BuddyCommand> {
+    let mentions = text
+        .split_whitespace()
+        .map(parse_mention)
+        .collect::<Option<Vec<&str>>>()?;
+
+    match mentions.as_slice() {
+        [] => Some(BuddyCommand::Show { employee_id: None }),
+        [employee_id] => Some(BuddyCommand::Show {
+            employee_id: Some(employee_id.to_string()),
+        }),
+        [employee_id, buddy_id] if employee_id != buddy_id => Some(BuddyCommand::Assign {
+            employee_id: employee_id.to_string(),
+            buddy_id: buddy_id.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+#[post(
+    "/command/buddy",
+    data = "<command>",
+    format = "application/x-www-form-urlencoded"
+)]
+pub fn buddy_command_route(
+    conn: Result<DbConn, DbError>,
+    slack: &State<SharedSlackApi>,
+    command: SignedForm<SlashCommand>,
+) -> status::Custom<String> {
+    let command = command.into_inner();
+    println!("slash command: {} {}", command.command, command.text);
+
+    let buddy_command = match parse_buddy_command(&command.text) {
+        Some(buddy_command) => buddy_command,
+        None => return status::Custom(Status::Ok, USAGE.to_string()),
+    };
+
+    let reply = match buddy_command {
+        BuddyCommand::Show { employee_id } => {
+            let employee_id = employee_id.unwrap_or(command.user_id);
+            conn.and_then(|mut conn| show(&mut conn, &employee_id))
+        }
+        BuddyCommand::Assign { .. } if !is_admin(&command.user_id) => {
+            Ok("Sólo los admins pueden asignar buddies.".to_string())
+        }
+        BuddyCommand::Assign {
+            employee_id,
+            buddy_id,
+        } => conn.and_then(|mut conn| assign(&mut conn, slack, &employee_id, &buddy_id)),
+    };
+
+    match reply {
+        Ok(reply) => status::Custom(Status::Ok, reply),
+        Err(e) => status::Custom(Status::Ok, db_error_message(e)),
+    }
+}
+
+fn show(conn: &mut PgConnection, employee_id: &str) -> Result<String, DbError> {
+    let buddy = get_buddy(conn, employee_id)?;
+    let mentees = get_buddy_mentees(conn, employee_id)?;
+    Ok(buddies_template(employee_id, buddy.as_ref(), &mentees))
+}
+
+fn assign(
+    conn: &mut PgConnection,
+    slack: &SharedSlackApi,
+    employee_id: &str,
+    buddy_id: &str,
+) -> Result<String, DbError> {
+    for id in [employee_id, buddy_id] {
+        match get_employee(conn, id) {
+            Err(DbError::NotFound) => return Ok(employee_not_found_template(id)),
+            Err(e) => return Err(e),
+            Ok(_) => {}
+        }
+    }
+
+    let buddy = assign_buddy(conn, employee_id, buddy_id, Utc::now())?;
+    spawn_dms(
+        slack,
+        vec![
+            (employee_id.to_string(), your_buddy_dm_template(buddy_id)),
+            (
+                buddy_id.to_string(),
+                buddy_assigned_dm_template(employee_id),
+            ),
+        ],
+    );
+    Ok(buddy_assignment_template(&buddy))
+}
@@ -0,0 +1,236 @@
+use super::{db_error_message, is_admin, SlashCommand};
+use crate::authenticate::SignedForm;
+use crate::models::Projects;
+use crate::pg_database::{
+    checklists::{add_checklist_task, complete_task, get_checklist_progress, get_checklist_task},
+    pool::DbConn,
+    projects::{get_onboardee, get_project_by_name},
+    DbError,
+};
+use crate::utils::{
+    parse_mention::parse_mention,
+    response_templates::{
+        checklist_progress_template, checklist_task_added_template, project_not_found_template,
+    },
+};
+
+use chrono::Utc;
+use diesel::pg::PgConnection;
+use rocket::{http::Status, response::status};
+
+pub const USAGE: &str = "Uso:\n\
+    - `/checklist agregar <proyecto>: <tarea>`\n\
+    - `/checklist ver <proyecto> [@persona]`\n\
+    - `/checklist hecho <proyecto> <número de tarea> [@persona]`";
+
+#[derive(Debug, PartialEq)]
+pub enum ChecklistCommand {
+    AddTask {
+        name: String,
+        title: String,
+    },
+    Show {
+        name: String,
+        employee_id: Option<String>,
+    },
+    Complete {
+        name: String,
+        position: i32,
+        employee_id: Option<String>,
+    },
+}
+
+/// Splits an optional trailing mention off the tokens.
+fn split_mention<'a>(tokens: &'a [&'a str]) -> (&'a [&'a str], Option<String>) {
+    match tokens.split_last() {
+        Some((last, rest)) => match parse_mention(last) {
+            Some(id) => (rest, Some(id.to_string())),
+            None => (tokens, None),
+        },
+        None => (tokens, None),
+    }
+}
+
+/// Parses `<subcomando> <proyecto...> ...`. Project names may contain spaces,
+/// so `agregar` separates the task with `:` and the other subcommands read
+/// their arguments from the end.
+pub fn parse_checklist_command(text: &str) -> Option<ChecklistCommand> {
+    let text = text.trim();
+    let (subcommand, rest) = text.split_once(char::is_whitespace)?;
+
+    match subcommand.to_lowercase().as_str() {
+        "agregar" => {
+            let (name, title) = rest.split_once(':')?;
+            let (name, title) = (name.trim(), title.trim());
+            if name.is_empty() || title.is_empty() {
+                return None;
+            }
+            Some(ChecklistCommand::AddTask {
+                name: name.to_string(),
+                title: title.to_string(),
+            })
+        }
+        "ver" => {
+            let tokens = rest.split_whitespace().collect::<Vec<&str>>();
+            let (name_tokens, employee_id) = split_mention(&tokens);
+            if name_tokens.is_empty() {
+                return None;
+            }
+            Some(ChecklistCommand::Show {
+                name: name_tokens.join(" "),
+                employee_id,
+            })
+        }
+        "hecho" => {
+            let tokens = rest.split_whitespace().collect::<Vec<&str>>();
+            let (tokens, employee_id) = split_mention(&tokens);
+            let (position, name_tokens) = tokens.split_last()?;
+            if name_tokens.is_empty() {
+                return None;
+            }
+            Some(ChecklistCommand::Complete {
+                name: name_tokens.join(" "),
+                position: position.parse().ok().filter(|p| *p > 0)?,
+                employee_id,
+            })
+        }
+        _ => None,
+    }
+}
+
+#[post(
+    "/command/checklist",
+    data = "<command>",
+    format = "application/x-www-form-urlencoded"
+)]
+pub fn checklist_command_route(
+    conn: Result<DbConn, DbError>,
+    command: SignedForm<SlashCommand>,
+) -> status::Custom<String> {
+    let command = command.into_inner();
+    println!("slash command: {} {}", command.command, command.text);
+
+    let checklist_command = match parse_checklist_command(&command.text) {
+        Some(checklist_command) => checklist_command,
+        None => return status::Custom(Status::Ok, USAGE.to_string()),
+    };
+
+    let reply = conn.and_then(|mut conn| match checklist_command {
+        ChecklistCommand::AddTask { name, title } => {
+            add_task(&mut conn, &command.user_id, &name, &title)
+        }
+        ChecklistCommand::Show { name, employee_id } => {
+            let employee_id = employee_id.unwrap_or(command.user_id);
+            show(&mut conn, &name, &employee_id)
+        }
+        ChecklistCommand::Complete {
+            name,
+            position,
+            employee_id,
+        } => {
+            let employee_id = employee_id.unwrap_or(command.user_id.clone());
+            complete(&mut conn, &command.user_id, &name, position, &employee_id)
+        }
+    });
+
+    match reply {
+        Ok(reply) => status::Custom(Status::Ok, reply),
+        Err(e) => status::Custom(Status::Ok, db_error_message(e)),
+    }
+}
+
+fn add_task(
+    conn: &mut PgConnection,
+    user_id: &str,
+    name: &str,
+    title: &str,
+) -> Result<String, DbError> {
+    let project = match get_project_by_name(conn, name) {
+        Ok(project) => project,
+        Err(DbError::NotFound) => return Ok(project_not_found_template(name)),
+        Err(e) => return Err(e),
+    };
+
+    if project.admin_id != user_id && !is_admin(user_id) {
+        return Ok(format!(
+            "Sólo <@{}> puede editar el checklist de *{}*.",
+            project.admin_id, project.name
+        ));
+    }
+
+    let task = add_checklist_task(conn, project.id, title)?;
+    Ok(checklist_task_added_template(&project, &task))
+}
+
+fn show(conn: &mut PgConnection, name: &str, employee_id: &str) -> Result<String, DbError> {
+    let project = match get_project_by_name(conn, name) {
+        Ok(project) => project,
+        Err(DbError::NotFound) => return Ok(project_not_found_template(name)),
+        Err(e) => return Err(e),
+    };
+
+    match get_onboardee(conn, project.id, employee_id) {
+        Err(DbError::NotFound) => return Ok(not_an_onboardee(&project, employee_id)),
+        Err(e) => return Err(e),
+        Ok(_) => {}
+    }
+
+    let progress = get_checklist_progress(conn, project.id, employee_id)?;
+    Ok(checklist_progress_template(
+        &project,
+        employee_id,
+        &progress,
+    ))
+}
+
+fn complete(
+    conn: &mut PgConnection,
+    user_id: &str,
+    name: &str,
+    position: i32,
+    employee_id: &str,
+) -> Result<String, DbError> {
+    let project = match get_project_by_name(conn, name) {
+        Ok(project) => project,
+        Err(DbError::NotFound) => return Ok(project_not_found_template(name)),
+        Err(e) => return Err(e),
+    };
+
+    // The onboardee ticks their own tasks; the project admin can do it for them.
+    if employee_id != user_id && project.admin_id != user_id && !is_admin(user_id) {
+        return Ok(format!(
+            "Sólo <@{}> o <@{}> pueden marcar sus tareas en *{}*.",
+            employee_id, project.admin_id, project.name
+        ));
+    }
+
+    match get_onboardee(conn, project.id, employee_id) {
+        Err(DbError::NotFound) => return Ok(not_an_onboardee(&project, employee_id)),
+        Err(e) => return Err(e),
+        Ok(_) => {}
+    }
+
+    let task = match get_checklist_task(conn, project.id, position) {
+        Ok(task) => task,
+        Err(DbError::NotFound) => {
+            return Ok(format!(
+                "*{}* no tiene una tarea {}.",
+                project.name, position
+            ))
+        }
+        Err(e) => return Err(e),
+    };
+
+    complete_task(conn, task.id, employee_id, user_id, Utc::now())?;
+
+    let progress = get_checklist_progress(conn, project.id, employee_id)?;
+    Ok(checklist_progress_template(
+        &project,
+        employee_id,
+        &progress,
+    ))
+}
+
+fn not_an_onboardee(project: &Projects, employee_id: &str) -> String {
+    format!("<@{}> no es onboardee de *{}*.", employee_id, project.name)
+}
@@ -1,29 +1,53 @@
 #[cfg(test)]
 mod tests;
 
-use crate::pg_database::get_employee_by_ts_range;
+pub mod announcements;
+pub mod buddy;
+pub mod checklist;
+pub mod nuevos;
+pub mod offboard;
+pub mod project;
+
+use self::nuevos::{spawn_date_range_modal, spawn_nuevos_command, RangeModalMetadata};
+use crate::pg_database::DbError;
 use crate::utils::{
-    group_employees_by_month::group_employees_by_month, parse_interval::parse_interval,
-    response_templates::new_employees_template, ParseDateStrError,
+    parse_interval::parse_interval, parse_options::parse_options,
+    response_templates::searching_template, ParseDateStrError,
 };
 
-use rocket::{form::Form, http::Status, response::status};
-
-use serde::Deserialize;
+use crate::authenticate::SignedForm;
+use crate::cache::SharedEmployeeCache;
+use crate::pg_database::pool::DbPool;
+use crate::slack_api::SharedSlackApi;
 
-#[derive(Deserialize, Debug)]
-#[serde(tag = "command")]
-pub enum SlackCommand {
-    ListNewPeople,
-}
+use chrono_tz::Tz;
+use rocket::{http::Status, response::status, State};
+use std::env;
 
 #[derive(FromForm, Debug)]
 pub struct ListNewsEmployeesCommand {
-    pub token: String,
-    pub api_app_id: String,
     pub command: String,
     pub text: String,
+    /// Whose timezone the dates are read in.
+    pub user_id: String,
     pub response_url: String,
+    /// Lets the bot open a modal within 3 seconds of the command.
+    pub trigger_id: String,
+}
+
+/// Fields Slack sends with every slash command that the bot cares about.
+#[derive(FromForm, Debug)]
+pub struct SlashCommand {
+    pub command: String,
+    pub text: String,
+    pub user_id: String,
+}
+
+/// Admins are listed by Slack user id in `SLACK_ADMIN_IDS`, comma separated.
+pub fn is_admin(user_id: &str) -> bool {
+    env::var("SLACK_ADMIN_IDS")
+        .map(|ids| ids.split(',').any(|id| id.trim() == user_id))
+        .unwrap_or(false)
 }
 
 #[post(
@@ -31,41 +55,98 @@ pub struct ListNewsEmployeesCommand {
     data = "<command>",
     format = "application/x-www-form-urlencoded"
 )]
-pub fn slash_command_route(command: Form<ListNewsEmployeesCommand>) -> status::Custom<String> {
+pub fn slash_command_route(
+    pool: &State<DbPool>,
+    cache: &State<SharedEmployeeCache>,
+    slack: &State<SharedSlackApi>,
+    command: SignedForm<ListNewsEmployeesCommand>,
+) -> status::Custom<String> {
+    let command = command.into_inner();
     println!("slash command: {} {}", command.command, command.text);
-    let parsed = parse_interval(&command.text);
-    match parsed {
-        Ok((from, to)) => {
-            let employees = get_employee_by_ts_range(from, to);
+    let (dates, options) = match parse_options(&command.text) {
+        Ok(parsed) => parsed,
+        Err(e) => return parse_error_reply(e, &command.text),
+    };
 
-            let employees_by_month = group_employees_by_month(employees);
+    // Without dates, they are picked in a modal that answers like the command.
+    // Its task looks up where the user is, so Slack gets its ack right away.
+    if dates.is_empty() {
+        spawn_date_range_modal(
+            slack.inner().clone(),
+            command.user_id,
+            command.trigger_id,
+            RangeModalMetadata {
+                response_url: command.response_url,
+                include_inactive: options.include_inactive,
+                in_channel: options.in_channel,
+                group_by_country: options.group_by_country,
+                tz: Tz::UTC,
+            },
+            options.countries,
+        );
+        return status::Custom(Status::Ok, String::new());
+    }
 
-            let formatted_employees = new_employees_template(from.and_utc().timestamp(), to.and_utc().timestamp(), employees_by_month);
+    // Mistakes don't depend on the timezone, so they are answered right away
+    // along with the dates as typed. Only the background task resolves them,
+    // where the user is.
+    match parse_interval(&dates, Tz::UTC) {
+        Ok(_) => {
+            let reply = searching_template(&dates);
+            spawn_nuevos_command(
+                pool.inner().clone(),
+                cache.inner().clone(),
+                slack.inner().clone(),
+                command,
+                dates,
+                options,
+            );
+
+            status::Custom(Status::Ok, reply)
+        }
+        Err(e) => parse_error_reply(e, &command.text),
+    }
+}
+
+fn parse_error_reply(e: ParseDateStrError, text: &str) -> status::Custom<String> {
+    status::Custom(Status::Ok, parse_error_message(e, text))
+}
+
+fn parse_error_message(e: ParseDateStrError, text: &str) -> String {
+    match e {
+        ParseDateStrError::Date(invalid) => format!(
+            "Fecha invalida: {}. Escribí /ayuda para ver opciones de formato.",
+            invalid
+        ),
+        ParseDateStrError::DatePart(invalid) => format!(
+            "El fragmento de fecha: {} de {} es inválido. Escribí /ayuda para ver opciones de formato.",
+            invalid, text
+        ),
+        ParseDateStrError::Interval(invalid) => format!(
+            "No entendí el intervalo {}: pasá una fecha, dos fechas, \"desde <fecha>\" o \"hasta <fecha>\". Escribí /ayuda para ver opciones de formato.",
+            invalid
+        ),
+        ParseDateStrError::ReversedInterval(from, to) => format!(
+            "El intervalo termina antes de empezar: va del {} al {}. Poné primero la fecha más vieja.",
+            from, to
+        ),
+        ParseDateStrError::InvalidOption(invalid) => format!(
+            "La opción {} es inválida. Escribí /ayuda para ver las opciones disponibles.",
+            invalid
+        ),
+        ParseDateStrError::NoDate => {
+            "No se recibió fecha. Escribí /ayuda para ver opciones de formato.".to_string()
+        }
+    }
+}
 
-            status::Custom(
-                Status::Ok,
-                formatted_employees,
-            )
+fn db_error_message(e: DbError) -> String {
+    println!("slash command failed: {}", e);
+    match e {
+        DbError::Connection(_) => {
+            "No pude conectarme a la base de datos. Probá de nuevo en unos minutos.".to_string()
         }
-        Err(e) => match e {
-            ParseDateStrError::Date(invalid) => {
-                status::Custom(Status::Ok, format!("Fecha invalida: {}. Escribí /ayuda para ver opciones de formato.", invalid))
-            }
-            ParseDateStrError::DatePart(invalid) => status::Custom(
-                Status::Ok,
-                format!(
-                    "El fragmento de fecha: {} de {} es inválido. Escribí /ayuda para ver opciones de formato.",
-                    invalid, command.text
-                ),
-            ),
-            ParseDateStrError::Interval(invalid) => {
-                status::Custom(Status::Ok, format!("El intervalo {} es", invalid))
-            }
-            ParseDateStrError::NoDate => status::Custom(
-                Status::Ok,
-                "No se recibió fecha. Escribí /ayuda para ver opciones de formato.".to_string(),
-            ),
-        },
+        _ => "Ocurrió un error al consultar la base de datos.".to_string(),
     }
 }
 
@@ -73,7 +154,18 @@ pub fn slash_command_route(command: Form<ListNewsEmployeesCommand>) -> status::C
 pub fn help_command_route() -> status::Custom<String> {
     let help_message = "
                     - Para listar nuevos empleados dentro de un rango de fechas específico, escribí `/nuevos <fecha_inicio> <fecha_fin>`.\n\
-                    - Podés usar fechas completas (DD/MM/YYYY), mes y año (MM/YYYY) o sólo año (YYYY).";
+                    - Podés usar fechas completas (DD/MM/YYYY o YYYY-MM-DD), mes y año (MM/YYYY o YYYY-MM), sólo año (YYYY), semanas ISO (2024-W07) o trimestres (Q1/2024 o T1/2024).\n\
+                    - También entiendo `hoy`, `ayer`, `esta semana`, `semana pasada`, `este mes`, `mes pasado`, `este año`, `año pasado`, `ultimos 30 dias` y meses como `enero 2024`.\n\
+                    - Dejá un extremo abierto con `desde 03/2024` (hasta hoy) o `hasta 2023` (desde el primer ingreso), o combinalos: `desde 2022 hasta 2023`.\n\
+                    - Sin fechas, `/nuevos` abre un formulario para elegirlas.\n\
+                    - Agregá `bajas:si` para incluir a quienes ya se fueron.\n\
+                    - Agregá `publico:si` para que la respuesta la vea todo el canal.\n\
+                    - Filtrá por país con `pais:argentina` (usá `_` en lugar de espacios, podés repetirlo) y agregá `agrupar:pais` para separar cada mes por país.\n\
+                    - Los admins pueden registrar una baja con `/baja @persona [DD/MM/YYYY]`.\n\
+                    - Para manejar proyectos y sus onboardees usá `/proyecto crear|agregar|listar`.\n\
+                    - Para ver y marcar las tareas de onboarding usá `/checklist agregar|ver|hecho`.\n\
+                    - Para ver o asignar buddies usá `/buddy [@persona] [@buddy]`.\n\
+                    - Los admins pueden configurar el anuncio de nuevos ingresos con `/anuncios`.";
 
     status::Custom(Status::Ok, help_message.to_string())
 }
@@ -0,0 +1,290 @@
+use super::{db_error_message, parse_error_message, ListNewsEmployeesCommand};
+use crate::cache::{get_or_load, EmployeeCache, RangeKey, SharedEmployeeCache};
+use crate::pg_database::{
+    get_employee_by_ts_range, get_first_join_date, pool::DbPool, DbError, EmployeeFilter,
+};
+use crate::slack_api::{blocks::Block, ResponseType, SharedSlackApi, SlackApi, SlashResponse};
+use crate::utils::{
+    group_employees_by_month::group_employees_by_month,
+    paginate_employees::paginate_employees,
+    parse_interval::parse_interval,
+    parse_options::NuevosOptions,
+    response_blocks::{date_range_modal, new_employees_blocks, pagination_blocks},
+    response_templates::new_employees_template,
+};
+
+use chrono::{DateTime, Datelike, Utc};
+use chrono_tz::Tz;
+use rocket::tokio::task::spawn_blocking;
+use serde::{Deserialize, Serialize};
+
+/// Employees per message. Each one takes two blocks, each month two more and,
+/// when grouping, each country one more, so a page stays under Slack's 50
+/// blocks.
+pub const PAGE_SIZE: usize = 9;
+
+/// `action_id` of the "Anterior"/"Siguiente" buttons.
+pub const PAGE_ACTION: &str = "nuevos_page";
+
+/// `callback_id` of the modal `/nuevos` opens when it gets no dates.
+pub const RANGE_CALLBACK: &str = "nuevos_rango";
+pub const FROM_BLOCK: &str = "desde";
+pub const TO_BLOCK: &str = "hasta";
+pub const COUNTRY_BLOCK: &str = "pais";
+/// `action_id` of every input in the modal; their blocks tell them apart.
+pub const INPUT_ACTION: &str = "valor";
+
+/// What the modal carries in `private_metadata` to answer like the slash
+/// command would have.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct RangeModalMetadata {
+    /// Still valid for 30 minutes after the command.
+    pub response_url: String,
+    pub include_inactive: bool,
+    pub in_channel: bool,
+    #[serde(default)]
+    pub group_by_country: bool,
+    /// The timezone of whoever ran the command.
+    #[serde(default)]
+    pub tz: Tz,
+}
+
+/// One page of a `/nuevos` answer. Buttons carry it as their value, so the
+/// interactivity endpoint can query the next page on its own.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct NuevosQuery {
+    /// `None` for "hasta <fecha>" until the first join date is looked up.
+    pub from: Option<i64>,
+    pub to: i64,
+    pub include_inactive: bool,
+    pub in_channel: bool,
+    #[serde(default)]
+    pub countries: Vec<String>,
+    #[serde(default)]
+    pub group_by_country: bool,
+    /// Whose calendar the dates and months follow.
+    #[serde(default)]
+    pub tz: Tz,
+    pub page: usize,
+}
+
+impl NuevosQuery {
+    fn interval(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        let [from, to] = [self.from.unwrap_or_default(), self.to]
+            .map(|ts| DateTime::from_timestamp(ts, 0).unwrap_or_default());
+        (from, to)
+    }
+
+    fn response_type(&self) -> ResponseType {
+        if self.in_channel {
+            ResponseType::InChannel
+        } else {
+            ResponseType::Ephemeral
+        }
+    }
+
+    fn button_value(&self, page: usize) -> String {
+        serde_json::to_string(&NuevosQuery {
+            page,
+            ..self.clone()
+        })
+        .unwrap()
+    }
+}
+
+/// An open start becomes the first join date, so the answer and its buttons
+/// show an actual date.
+fn with_first_join_date(pool: &DbPool, query: &NuevosQuery) -> Result<NuevosQuery, DbError> {
+    if query.from.is_some() {
+        return Ok(query.clone());
+    }
+
+    let first_join_date = get_first_join_date(&mut *pool.get()?)?;
+    let from = first_join_date.map_or(query.to, |date| date.timestamp().min(query.to));
+    Ok(NuevosQuery {
+        from: Some(from),
+        ..query.clone()
+    })
+}
+
+/// The page as text and as blocks, or the error message to answer with. A
+/// connection is only checked out when the cache can't answer.
+pub(super) fn list_new_employees(
+    pool: &DbPool,
+    cache: &dyn EmployeeCache,
+    query: &NuevosQuery,
+) -> Result<(String, Vec<Block>), String> {
+    let query = &with_first_join_date(pool, query).map_err(db_error_message)?;
+    let (from, to) = query.interval();
+    let from_ts = from.timestamp();
+    let filter = EmployeeFilter {
+        include_inactive: query.include_inactive,
+        countries: query.countries.clone(),
+    };
+    let key = RangeKey::new(from, to, &filter);
+    let employees = get_or_load(cache, &key, || {
+        get_employee_by_ts_range(&mut *pool.get()?, from, to, &filter)
+    })
+    .map_err(db_error_message)?;
+
+    let (employees_by_month, total_pages) = paginate_employees(
+        group_employees_by_month(employees, query.tz),
+        query.page,
+        PAGE_SIZE,
+    );
+    let mut blocks = new_employees_blocks(
+        from_ts,
+        query.to,
+        &employees_by_month,
+        query.group_by_country,
+        query.tz,
+    );
+    blocks.extend(pagination_blocks(
+        query.page,
+        total_pages,
+        PAGE_ACTION,
+        |page| query.button_value(page),
+    ));
+    let text = new_employees_template(
+        from_ts,
+        query.to,
+        employees_by_month,
+        query.group_by_country,
+        query.tz,
+    );
+    Ok((text, blocks))
+}
+
+/// Where `user_id` is, per their Slack profile, or UTC if Slack doesn't say.
+async fn user_timezone_or_utc(slack: &dyn SlackApi, user_id: &str) -> Tz {
+    slack.user_timezone(user_id).await.unwrap_or_else(|e| {
+        println!("Timezone of {} unknown, using UTC: {}", user_id, e);
+        Tz::UTC
+    })
+}
+
+/// Only the person who ran the command sees errors.
+fn error_response(text: String) -> SlashResponse {
+    SlashResponse {
+        response_type: ResponseType::Ephemeral,
+        text,
+        blocks: None,
+        replace_original: false,
+    }
+}
+
+/// Answers `/nuevos <dates>` in the background: the dates are days where the
+/// user is, which takes a call to Slack to know.
+pub fn spawn_nuevos_command(
+    pool: DbPool,
+    cache: SharedEmployeeCache,
+    slack: SharedSlackApi,
+    command: ListNewsEmployeesCommand,
+    dates: String,
+    options: NuevosOptions,
+) {
+    rocket::tokio::spawn(async move {
+        let tz = user_timezone_or_utc(slack.as_ref(), &command.user_id).await;
+        match parse_interval(&dates, tz) {
+            Ok((from, to)) => {
+                let query = NuevosQuery {
+                    from: from.map(|from| from.timestamp()),
+                    to: to.timestamp(),
+                    include_inactive: options.include_inactive,
+                    in_channel: options.in_channel,
+                    countries: options.countries,
+                    group_by_country: options.group_by_country,
+                    tz,
+                    page: 0,
+                };
+                respond_new_employees(pool, cache, slack, command.response_url, query, false).await;
+            }
+            Err(e) => {
+                let response = error_response(parse_error_message(e, &command.text));
+                if let Err(e) = slack.respond(&command.response_url, &response).await {
+                    println!("/nuevos response not sent: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// Lists the page in the background and posts it to `response_url`, since big
+/// ranges, a slow database or a busy pool would miss Slack's 3 seconds.
+pub fn spawn_new_employees_response(
+    pool: DbPool,
+    cache: SharedEmployeeCache,
+    slack: SharedSlackApi,
+    response_url: String,
+    query: NuevosQuery,
+    replace_original: bool,
+) {
+    rocket::tokio::spawn(respond_new_employees(
+        pool,
+        cache,
+        slack,
+        response_url,
+        query,
+        replace_original,
+    ));
+}
+
+async fn respond_new_employees(
+    pool: DbPool,
+    cache: SharedEmployeeCache,
+    slack: SharedSlackApi,
+    response_url: String,
+    query: NuevosQuery,
+    replace_original: bool,
+) {
+    let response_type = query.response_type();
+    let response = spawn_blocking(move || list_new_employees(&pool, cache.as_ref(), &query))
+        .await
+        .unwrap_or_else(|e| Err(db_error_message(DbError::Query(e.to_string()))));
+
+    let response = match response {
+        Ok((text, blocks)) => SlashResponse {
+            response_type,
+            text,
+            blocks: Some(blocks),
+            replace_original,
+        },
+        // Errors stay private, whoever was meant to see the list.
+        Err(text) => error_response(text),
+    };
+    if let Err(e) = slack.respond(&response_url, &response).await {
+        println!("/nuevos response not sent: {}", e);
+    }
+}
+
+/// Opens the date range modal, from the start of this month until today where
+/// the user is and with the command's countries. If Slack refuses, the command gets a text
+/// answer instead.
+pub fn spawn_date_range_modal(
+    slack: SharedSlackApi,
+    user_id: String,
+    trigger_id: String,
+    mut metadata: RangeModalMetadata,
+    countries: Vec<String>,
+) {
+    rocket::tokio::spawn(async move {
+        metadata.tz = user_timezone_or_utc(slack.as_ref(), &user_id).await;
+        let today = Utc::now().with_timezone(&metadata.tz).date_naive();
+        let modal = date_range_modal(
+            serde_json::to_string(&metadata).unwrap(),
+            today.with_day(1).unwrap(),
+            today,
+            &countries,
+        );
+
+        if let Err(e) = slack.open_view(&trigger_id, &modal).await {
+            println!("/nuevos modal not opened: {}", e);
+            let response = error_response(
+                "No pude abrir el selector de fechas. Escribí /ayuda para ver cómo pasarlas en el comando.".to_string(),
+            );
+            if let Err(e) = slack.respond(&metadata.response_url, &response).await {
+                println!("/nuevos response not sent: {}", e);
+            }
+        }
+    });
+}
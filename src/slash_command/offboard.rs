@@ -0,0 +1,68 @@
+use super::{is_admin, SlashCommand};
+use crate::authenticate::SignedForm;
+use crate::cache::{spawn_invalidate, SharedEmployeeCache};
+use crate::pg_database::{mark_employee_left, pool::DbConn, DbError, LeftBy};
+use crate::utils::{
+    parse_date_str::parse_date_str,
+    parse_mention::parse_mention,
+    response_templates::{employee_left_template, employee_not_found_template},
+    DateRound,
+};
+
+use chrono::Utc;
+use rocket::{http::Status, response::status, State};
+
+const USAGE: &str = "Uso: /baja @persona [DD/MM/YYYY]";
+
+#[post(
+    "/command/baja",
+    data = "<command>",
+    format = "application/x-www-form-urlencoded"
+)]
+pub fn offboard_command_route(
+    conn: Result<DbConn, DbError>,
+    cache: &State<SharedEmployeeCache>,
+    command: SignedForm<SlashCommand>,
+) -> status::Custom<String> {
+    let command = command.into_inner();
+    println!("slash command: {} {}", command.command, command.text);
+
+    if !is_admin(&command.user_id) {
+        return status::Custom(
+            Status::Ok,
+            "Sólo los admins pueden registrar bajas.".to_string(),
+        );
+    }
+
+    let args = command.text.split_whitespace().collect::<Vec<&str>>();
+    let (employee_id, left_date) = match args.as_slice() {
+        [mention] => (parse_mention(mention), Ok(Utc::now().date_naive())),
+        [mention, date] => (
+            parse_mention(mention),
+            parse_date_str(date, DateRound::Floor).map(|date| date.date()),
+        ),
+        _ => return status::Custom(Status::Ok, USAGE.to_string()),
+    };
+
+    let (employee_id, left_date) = match (employee_id, left_date) {
+        (Some(id), Ok(date)) => (id, date),
+        _ => return status::Custom(Status::Ok, USAGE.to_string()),
+    };
+
+    let result = conn
+        .and_then(|mut conn| mark_employee_left(&mut conn, employee_id, left_date, LeftBy::Admin));
+
+    match result {
+        Ok(employee) => {
+            spawn_invalidate(cache.inner());
+            status::Custom(
+                Status::Ok,
+                employee_left_template(&employee.id, employee.left_date.unwrap_or(left_date)),
+            )
+        }
+        Err(DbError::NotFound) => {
+            status::Custom(Status::Ok, employee_not_found_template(employee_id))
+        }
+        Err(e) => status::Custom(Status::Ok, super::db_error_message(e)),
+    }
+}
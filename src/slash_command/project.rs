@@ -0,0 +1,185 @@
+use super::{db_error_message, is_admin, SlashCommand};
+use crate::authenticate::SignedForm;
+use crate::pg_database::{
+    get_employee,
+    pool::DbConn,
+    projects::{add_onboardee, create_project, get_project_by_name, get_project_onboardees},
+    DbError,
+};
+use crate::utils::{
+    parse_date_str::parse_date_str,
+    parse_mention::parse_mention,
+    response_templates::{
+        employee_not_found_template, onboardee_added_template, project_created_template,
+        project_not_found_template, project_onboardees_template,
+    },
+    DateRound,
+};
+
+use chrono::{NaiveDate, Utc};
+use diesel::pg::PgConnection;
+use rocket::{http::Status, response::status};
+
+pub const USAGE: &str = "Uso:\n\
+    - `/proyecto crear <nombre> [@admin]`\n\
+    - `/proyecto agregar <nombre> @persona [DD/MM/YYYY]`\n\
+    - `/proyecto listar <nombre>`";
+
+#[derive(Debug, PartialEq)]
+pub enum ProjectCommand {
+    Create {
+        name: String,
+        admin_id: Option<String>,
+    },
+    AddOnboardee {
+        name: String,
+        employee_id: String,
+        onboarding_date: Option<NaiveDate>,
+    },
+    List {
+        name: String,
+    },
+}
+
+/// Parses `<subcomando> <nombre...> [@persona] [fecha]`. The project name is
+/// everything up to the first mention, so it may contain spaces.
+pub fn parse_project_command(text: &str) -> Option<ProjectCommand> {
+    let tokens = text.split_whitespace().collect::<Vec<&str>>();
+    let (subcommand, rest) = tokens.split_first()?;
+
+    let mention_at = rest.iter().position(|t| parse_mention(t).is_some());
+    let (name_tokens, after_name) = rest.split_at(mention_at.unwrap_or(rest.len()));
+    if name_tokens.is_empty() {
+        return None;
+    }
+    let name = name_tokens.join(" ");
+    let mention = after_name.first().and_then(|t| parse_mention(t));
+
+    match (
+        subcommand.to_lowercase().as_str(),
+        mention,
+        after_name.len(),
+    ) {
+        ("crear", admin_id, 0..=1) => Some(ProjectCommand::Create {
+            name,
+            admin_id: admin_id.map(String::from),
+        }),
+        ("agregar", Some(employee_id), 1) => Some(ProjectCommand::AddOnboardee {
+            name,
+            employee_id: employee_id.to_string(),
+            onboarding_date: None,
+        }),
+        ("agregar", Some(employee_id), 2) => Some(ProjectCommand::AddOnboardee {
+            name,
+            employee_id: employee_id.to_string(),
+            onboarding_date: Some(parse_date_str(after_name[1], DateRound::Floor).ok()?.date()),
+        }),
+        ("listar", None, 0) => Some(ProjectCommand::List { name }),
+        _ => None,
+    }
+}
+
+#[post(
+    "/command/proyecto",
+    data = "<command>",
+    format = "application/x-www-form-urlencoded"
+)]
+pub fn project_command_route(
+    conn: Result<DbConn, DbError>,
+    command: SignedForm<SlashCommand>,
+) -> status::Custom<String> {
+    let command = command.into_inner();
+    println!("slash command: {} {}", command.command, command.text);
+
+    let project_command = match parse_project_command(&command.text) {
+        Some(project_command) => project_command,
+        None => return status::Custom(Status::Ok, USAGE.to_string()),
+    };
+
+    let reply = conn.and_then(|mut conn| match project_command {
+        ProjectCommand::Create { name, admin_id } => {
+            let admin_id = admin_id.unwrap_or(command.user_id);
+            create(&mut conn, &name, &admin_id)
+        }
+        ProjectCommand::AddOnboardee {
+            name,
+            employee_id,
+            onboarding_date,
+        } => add(
+            &mut conn,
+            &command.user_id,
+            &name,
+            &employee_id,
+            onboarding_date.unwrap_or_else(|| Utc::now().date_naive()),
+        ),
+        ProjectCommand::List { name } => list(&mut conn, &name),
+    });
+
+    match reply {
+        Ok(reply) => status::Custom(Status::Ok, reply),
+        Err(e) => status::Custom(Status::Ok, db_error_message(e)),
+    }
+}
+
+fn create(conn: &mut PgConnection, name: &str, admin_id: &str) -> Result<String, DbError> {
+    match get_employee(conn, admin_id) {
+        Err(DbError::NotFound) => return Ok(employee_not_found_template(admin_id)),
+        Err(e) => return Err(e),
+        Ok(_) => {}
+    }
+
+    match create_project(conn, name, admin_id) {
+        Ok(project) => Ok(project_created_template(&project)),
+        Err(DbError::UniqueViolation(_)) => {
+            Ok(format!("Ya existe un proyecto llamado *{}*.", name))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn add(
+    conn: &mut PgConnection,
+    user_id: &str,
+    name: &str,
+    employee_id: &str,
+    onboarding_date: NaiveDate,
+) -> Result<String, DbError> {
+    let project = match get_project_by_name(conn, name) {
+        Ok(project) => project,
+        Err(DbError::NotFound) => return Ok(project_not_found_template(name)),
+        Err(e) => return Err(e),
+    };
+
+    if project.admin_id != user_id && !is_admin(user_id) {
+        return Ok(format!(
+            "Sólo <@{}> puede agregar onboardees a *{}*.",
+            project.admin_id, project.name
+        ));
+    }
+
+    match get_employee(conn, employee_id) {
+        Err(DbError::NotFound) => return Ok(employee_not_found_template(employee_id)),
+        Err(e) => return Err(e),
+        Ok(_) => {}
+    }
+
+    match add_onboardee(conn, project.id, employee_id, onboarding_date) {
+        Ok(onboardee) => Ok(onboardee_added_template(&project, &onboardee)),
+        Err(DbError::UniqueViolation(_)) => Ok(format!(
+            "<@{}> ya es onboardee de *{}*.",
+            employee_id, project.name
+        )),
+        Err(e) => Err(e),
+    }
+}
+
+fn list(conn: &mut PgConnection, name: &str) -> Result<String, DbError> {
+    let project = match get_project_by_name(conn, name) {
+        Ok(project) => project,
+        Err(DbError::NotFound) => return Ok(project_not_found_template(name)),
+        Err(e) => return Err(e),
+    };
+
+    let onboardees = get_project_onboardees(conn, project.id)?;
+    Ok(project_onboardees_template(&project, &onboardees))
+}
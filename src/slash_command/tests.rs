@@ -5,3 +5,551 @@ mod test_slash_command {
         assert_eq!(1, 1);
     }
 }
+
+#[cfg(test)]
+mod test_slash_command_route {
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+    use rocket::{
+        http::{ContentType, Header, RawStr, Status},
+        local::blocking::Client,
+    };
+
+    use crate::authenticate::{sign, SigningSecret, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+    use crate::cache::{MemoryEmployeeCache, RangeKey, SharedEmployeeCache};
+    use crate::models::test_employee;
+    use crate::pg_database::{pool::unavailable_pool, EmployeeFilter};
+    use crate::slack_api::{ChannelSlackApi, ResponseType, SharedSlackApi, SlackCall};
+    use crate::slash_command::{
+        announcements::announcements_command_route,
+        buddy::buddy_command_route,
+        nuevos::{RangeModalMetadata, RANGE_CALLBACK},
+        offboard::offboard_command_route,
+        slash_command_route,
+    };
+    use std::{
+        sync::{mpsc::Receiver, Arc},
+        time::Duration,
+    };
+
+    const SECRET: &str = "test_signing_secret";
+    const RESPONSE_URL: &str = "https://hooks.slack.com/commands/T1/2/3";
+
+    fn client() -> Client {
+        client_with_calls().0
+    }
+
+    /// The database is down, and the user is in Buenos Aires.
+    fn client_with_calls() -> (Client, Receiver<SlackCall>) {
+        let (slack, calls) = ChannelSlackApi::new(Some(Tz::America__Argentina__Buenos_Aires));
+        let rocket = rocket::build()
+            .manage(SigningSecret(SECRET.to_string()))
+            .manage(unavailable_pool())
+            .manage(Arc::new(slack) as SharedSlackApi)
+            .manage(Arc::new(MemoryEmployeeCache::default()) as SharedEmployeeCache)
+            .mount(
+                "/",
+                routes![
+                    slash_command_route,
+                    offboard_command_route,
+                    announcements_command_route,
+                    buddy_command_route
+                ],
+            );
+        (Client::tracked(rocket).unwrap(), calls)
+    }
+
+    /// Waits for what the background task of a command did.
+    fn next_call(calls: &Receiver<SlackCall>) -> SlackCall {
+        calls
+            .recv_timeout(Duration::from_secs(5))
+            .expect("no call to Slack")
+    }
+
+    fn post_command(client: &Client, command: &str, text: &str) -> (Status, String) {
+        let ts = Utc::now().timestamp().to_string();
+        let body = format!(
+            "command=%2F{}&text={}&user_id=U2CERLKJA&trigger_id=1.2.abc&response_url={}",
+            command,
+            text,
+            RawStr::new(RESPONSE_URL).percent_encode()
+        );
+
+        let res = client
+            .post(format!("/command/{}", command))
+            .header(ContentType::Form)
+            .header(Header::new(TIMESTAMP_HEADER, ts.clone()))
+            .header(Header::new(SIGNATURE_HEADER, sign(SECRET, &ts, &body)))
+            .body(body)
+            .dispatch();
+
+        (res.status(), res.into_string().unwrap())
+    }
+
+    #[test]
+    fn should_reply_in_slack_when_database_is_unavailable() {
+        let (client, calls) = client_with_calls();
+
+        let (status, text) = post_command(&client, "nuevos", "2024+publico%3Asi");
+
+        assert_eq!(status, Status::Ok);
+        assert_eq!(text, "Buscando a los que entraron según \"2024\"...");
+        // The result is posted from a background task, privately since it failed.
+        match next_call(&calls) {
+            SlackCall::Respond {
+                response_url,
+                response,
+            } => {
+                assert_eq!(response_url, RESPONSE_URL);
+                assert_eq!(response.response_type, ResponseType::Ephemeral);
+                assert!(response
+                    .text
+                    .contains("No pude conectarme a la base de datos"));
+            }
+            other => panic!("unexpected call {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_open_date_range_modal_without_dates() {
+        let (client, calls) = client_with_calls();
+
+        let (status, text) = post_command(&client, "nuevos", "bajas%3Asi");
+
+        assert_eq!(status, Status::Ok);
+        assert_eq!(text, "");
+        match next_call(&calls) {
+            SlackCall::OpenView { trigger_id, modal } => {
+                assert_eq!(trigger_id, "1.2.abc");
+                assert_eq!(modal.callback_id, RANGE_CALLBACK);
+                let metadata: RangeModalMetadata =
+                    serde_json::from_str(&modal.private_metadata).unwrap();
+                assert!(metadata.include_inactive);
+                // The modal's dates are read where the user is.
+                assert_eq!(metadata.tz, Tz::America__Argentina__Buenos_Aires);
+            }
+            other => panic!("unexpected call {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_read_dates_where_the_user_is() {
+        let (client, calls) = client_with_calls();
+        // 2024 in Buenos Aires, UTC-3.
+        let [from, to] =
+            [1704078000, 1735700399].map(|ts| DateTime::from_timestamp(ts, 0).unwrap());
+        let key = RangeKey::new(from, to, &EmployeeFilter::default());
+        let cache = client.rocket().state::<SharedEmployeeCache>().unwrap();
+        cache
+            .set(0, &key, &[test_employee("U1", 1704078000)])
+            .unwrap();
+
+        let (status, text) = post_command(&client, "nuevos", "2024");
+
+        assert_eq!(status, Status::Ok);
+        assert_eq!(text, "Buscando a los que entraron según \"2024\"...");
+        match next_call(&calls) {
+            SlackCall::Respond { response, .. } => assert!(response.text.contains("<@U1>")),
+            other => panic!("unexpected call {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_search_since_the_first_join_with_hasta() {
+        let (client, calls) = client_with_calls();
+
+        let (status, text) = post_command(&client, "nuevos", "hasta+2023");
+
+        assert_eq!(status, Status::Ok);
+        assert_eq!(text, "Buscando a los que entraron según \"hasta 2023\"...");
+        // Looking up the first join date needs the database too.
+        match next_call(&calls) {
+            SlackCall::Respond { response, .. } => assert!(response
+                .text
+                .contains("No pude conectarme a la base de datos")),
+            other => panic!("unexpected call {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_reject_reversed_intervals() {
+        let client = client();
+
+        let (status, text) = post_command(&client, "nuevos", "2024+2023");
+
+        assert_eq!(status, Status::Ok);
+        assert_eq!(
+            text,
+            "El intervalo termina antes de empezar: va del 01/01/2024 al 31/12/2023. Poné primero la fecha más vieja."
+        );
+    }
+
+    #[test]
+    fn should_reject_unknown_nuevos_options() {
+        let client = client();
+
+        let (status, text) = post_command(&client, "nuevos", "2024+color%3Arojo");
+
+        assert_eq!(status, Status::Ok);
+        assert!(text.starts_with("La opción color:rojo es inválida"));
+    }
+
+    #[test]
+    fn should_only_let_admins_offboard() {
+        let client = client();
+
+        let (status, text) = post_command(&client, "baja", "%3C%40U123%3E");
+
+        assert_eq!(status, Status::Ok);
+        assert_eq!(text, "Sólo los admins pueden registrar bajas.");
+    }
+
+    #[test]
+    fn should_only_let_admins_configure_announcements() {
+        let client = client();
+
+        let (status, text) = post_command(&client, "anuncios", "activar");
+
+        assert_eq!(status, Status::Ok);
+        assert_eq!(text, "Sólo los admins pueden configurar los anuncios.");
+    }
+
+    #[test]
+    fn should_only_let_admins_assign_buddies() {
+        let client = client();
+
+        let (status, text) = post_command(&client, "buddy", "%3C%40U123%3E+%3C%40U456%3E");
+
+        assert_eq!(status, Status::Ok);
+        assert_eq!(text, "Sólo los admins pueden asignar buddies.");
+    }
+}
+
+#[cfg(test)]
+mod test_project_command {
+    use chrono::NaiveDate;
+
+    use crate::slash_command::project::{parse_project_command, ProjectCommand};
+
+    #[test]
+    fn should_parse_create_with_and_without_admin() {
+        assert_eq!(
+            parse_project_command("crear Pagos Online"),
+            Some(ProjectCommand::Create {
+                name: "Pagos Online".to_string(),
+                admin_id: None,
+            })
+        );
+        assert_eq!(
+            parse_project_command("crear Pagos <@U123|juan>"),
+            Some(ProjectCommand::Create {
+                name: "Pagos".to_string(),
+                admin_id: Some("U123".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn should_parse_add_onboardee() {
+        assert_eq!(
+            parse_project_command("agregar Pagos <@U123> 15/02/2024"),
+            Some(ProjectCommand::AddOnboardee {
+                name: "Pagos".to_string(),
+                employee_id: "U123".to_string(),
+                onboarding_date: NaiveDate::from_ymd_opt(2024, 2, 15),
+            })
+        );
+        assert_eq!(
+            parse_project_command("AGREGAR Pagos <@U123>"),
+            Some(ProjectCommand::AddOnboardee {
+                name: "Pagos".to_string(),
+                employee_id: "U123".to_string(),
+                onboarding_date: None,
+            })
+        );
+    }
+
+    #[test]
+    fn should_parse_list() {
+        assert_eq!(
+            parse_project_command("listar Pagos"),
+            Some(ProjectCommand::List {
+                name: "Pagos".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_commands() {
+        for text in [
+            "",
+            "crear",
+            "crear <@U123>",
+            "agregar Pagos",
+            "agregar Pagos <@U123> 31/02/2024",
+            "listar Pagos <@U123>",
+            "borrar Pagos",
+        ] {
+            assert_eq!(parse_project_command(text), None, "{}", text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_announcement_command {
+    use crate::slash_command::announcements::{parse_announcement_command, AnnouncementCommand};
+
+    #[test]
+    fn should_parse_toggles_and_show() {
+        assert_eq!(
+            parse_announcement_command(""),
+            Some(AnnouncementCommand::Show)
+        );
+        assert_eq!(
+            parse_announcement_command("ver"),
+            Some(AnnouncementCommand::Show)
+        );
+        assert_eq!(
+            parse_announcement_command("ACTIVAR"),
+            Some(AnnouncementCommand::Enable)
+        );
+        assert_eq!(
+            parse_announcement_command("desactivar"),
+            Some(AnnouncementCommand::Disable)
+        );
+    }
+
+    #[test]
+    fn should_parse_channel() {
+        assert_eq!(
+            parse_announcement_command("canal <#C123|bienvenidos>"),
+            Some(AnnouncementCommand::Channel("C123".to_string()))
+        );
+    }
+
+    #[test]
+    fn should_keep_spaces_in_free_text() {
+        assert_eq!(
+            parse_announcement_command("mensaje Llegó {usuario}  desde {pais}"),
+            Some(AnnouncementCommand::Template(
+                "Llegó {usuario}  desde {pais}".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_announcement_command("intro Saludá en el hilo"),
+            Some(AnnouncementCommand::Intro(Some(
+                "Saludá en el hilo".to_string()
+            )))
+        );
+        assert_eq!(
+            parse_announcement_command("intro"),
+            Some(AnnouncementCommand::Intro(None))
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_commands() {
+        for text in [
+            "activar ya",
+            "canal",
+            "canal general",
+            "canal <@U123>",
+            "mensaje",
+            "borrar",
+        ] {
+            assert_eq!(parse_announcement_command(text), None, "{}", text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_checklist_command {
+    use crate::slash_command::checklist::{parse_checklist_command, ChecklistCommand};
+
+    #[test]
+    fn should_parse_add_task() {
+        assert_eq!(
+            parse_checklist_command("agregar Pagos Online: Conocer a tu buddy"),
+            Some(ChecklistCommand::AddTask {
+                name: "Pagos Online".to_string(),
+                title: "Conocer a tu buddy".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn should_parse_show_with_and_without_mention() {
+        assert_eq!(
+            parse_checklist_command("ver Pagos Online"),
+            Some(ChecklistCommand::Show {
+                name: "Pagos Online".to_string(),
+                employee_id: None,
+            })
+        );
+        assert_eq!(
+            parse_checklist_command("VER Pagos <@U123|juan>"),
+            Some(ChecklistCommand::Show {
+                name: "Pagos".to_string(),
+                employee_id: Some("U123".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn should_parse_complete() {
+        assert_eq!(
+            parse_checklist_command("hecho Pagos Online 2"),
+            Some(ChecklistCommand::Complete {
+                name: "Pagos Online".to_string(),
+                position: 2,
+                employee_id: None,
+            })
+        );
+        assert_eq!(
+            parse_checklist_command("hecho Pagos 1 <@U123>"),
+            Some(ChecklistCommand::Complete {
+                name: "Pagos".to_string(),
+                position: 1,
+                employee_id: Some("U123".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_commands() {
+        for text in [
+            "",
+            "ver",
+            "ver <@U123>",
+            "agregar Pagos",
+            "agregar : tarea",
+            "agregar Pagos:",
+            "hecho Pagos",
+            "hecho 2",
+            "hecho Pagos 0",
+            "hecho Pagos dos",
+            "borrar Pagos",
+        ] {
+            assert_eq!(parse_checklist_command(text), None, "{}", text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_buddy_command {
+    use crate::slash_command::buddy::{parse_buddy_command, BuddyCommand};
+
+    #[test]
+    fn should_parse_show() {
+        assert_eq!(
+            parse_buddy_command(""),
+            Some(BuddyCommand::Show { employee_id: None })
+        );
+        assert_eq!(
+            parse_buddy_command("<@U123|juan>"),
+            Some(BuddyCommand::Show {
+                employee_id: Some("U123".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn should_parse_assign() {
+        assert_eq!(
+            parse_buddy_command("<@U123> <@U456|ana>"),
+            Some(BuddyCommand::Assign {
+                employee_id: "U123".to_string(),
+                buddy_id: "U456".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_commands() {
+        for text in [
+            "juan",
+            "<@U123> ana",
+            "<@U123> <@U123>",
+            "<@U1> <@U2> <@U3>",
+        ] {
+            assert_eq!(parse_buddy_command(text), None, "{}", text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_nuevos_pages {
+    use chrono::DateTime;
+    use chrono_tz::Tz;
+
+    use crate::cache::{EmployeeCache, MemoryEmployeeCache, RangeKey};
+    use crate::models::test_employee;
+    use crate::pg_database::{pool::unavailable_pool, EmployeeFilter};
+    use crate::slack_api::blocks::{Block, Element};
+    use crate::slash_command::nuevos::{list_new_employees, NuevosQuery, PAGE_ACTION, PAGE_SIZE};
+
+    const FROM: i64 = 1704067200; // 2024-01-01 UTC-0
+    const TO: i64 = 1735689599; // 2024-12-31 23:59:59 UTC-0
+
+    fn cache_with_employees(count: i64) -> MemoryEmployeeCache {
+        let cache = MemoryEmployeeCache::default();
+        let employees = (0..count)
+            .map(|i| test_employee(&format!("U{}", i), FROM + i * 86400))
+            .collect::<Vec<_>>();
+        let [from, to] = [FROM, TO].map(|ts| DateTime::from_timestamp(ts, 0).unwrap());
+        let key = RangeKey::new(from, to, &EmployeeFilter::default());
+        cache.set(0, &key, &employees).unwrap();
+        cache
+    }
+
+    fn query(page: usize) -> NuevosQuery {
+        NuevosQuery {
+            from: Some(FROM),
+            to: TO,
+            include_inactive: false,
+            in_channel: false,
+            countries: vec![],
+            group_by_country: false,
+            tz: Tz::UTC,
+            page,
+        }
+    }
+
+    #[test]
+    fn should_link_the_next_page_in_the_button_value() {
+        let cache = cache_with_employees(25);
+
+        let (text, blocks) = list_new_employees(&unavailable_pool(), &cache, &query(1)).unwrap();
+
+        assert_eq!(text.matches("- <@").count(), PAGE_SIZE);
+        assert!(blocks.len() <= 50);
+        assert!(blocks.contains(&Block::context(vec!["Página 2 de 3".to_string()])));
+        let Some(Block::Actions { elements }) = blocks.last() else {
+            panic!("no buttons in {:?}", blocks);
+        };
+        let values = elements
+            .iter()
+            .map(|element| match element {
+                Element::Button {
+                    action_id, value, ..
+                } => {
+                    assert_eq!(action_id, PAGE_ACTION);
+                    serde_json::from_str::<NuevosQuery>(value).unwrap()
+                }
+                other => panic!("not a button: {:?}", other),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![query(0), query(2)]);
+    }
+
+    #[test]
+    fn should_not_paginate_short_lists() {
+        let cache = cache_with_employees(3);
+
+        let (_, blocks) = list_new_employees(&unavailable_pool(), &cache, &query(0)).unwrap();
+
+        assert!(!blocks
+            .iter()
+            .any(|block| matches!(block, Block::Actions { .. })));
+    }
+}
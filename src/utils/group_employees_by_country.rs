@@ -0,0 +1,53 @@
+use std::collections::BTreeMap;
+
+use crate::models::Employee;
+
+/// Employees of a month by `country`, in alphabetical order. People without a
+/// country come first, under `None`.
+pub fn group_employees_by_country(
+    employees: &[Employee],
+) -> BTreeMap<Option<&str>, Vec<&Employee>> {
+    let mut employees_by_country: BTreeMap<Option<&str>, Vec<&Employee>> = BTreeMap::new();
+
+    for employee in employees {
+        employees_by_country
+            .entry(employee.country.as_deref())
+            .or_default()
+            .push(employee);
+    }
+    employees_by_country
+}
+
+#[cfg(test)]
+mod test_group_employees_by_country {
+    use std::collections::BTreeMap;
+
+    use super::group_employees_by_country;
+    use crate::models::test_employee;
+
+    #[test]
+    fn test_group_employees_by_country() {
+        let mut chile = test_employee("ABC123", 1707745600);
+        chile.country = Some("chile".to_string());
+        let mut argentina1 = test_employee("DEF456", 1707745600);
+        argentina1.country = Some("argentina".to_string());
+        let mut argentina2 = test_employee("GHI789", 1708048800);
+        argentina2.country = Some("argentina".to_string());
+        let unknown = test_employee("JKL012", 1708048800);
+        let employees = vec![
+            chile.clone(),
+            argentina1.clone(),
+            unknown.clone(),
+            argentina2.clone(),
+        ];
+
+        let result = group_employees_by_country(&employees);
+
+        let expected = BTreeMap::from([
+            (None, vec![&unknown]),
+            (Some("argentina"), vec![&argentina1, &argentina2]),
+            (Some("chile"), vec![&chile]),
+        ]);
+        assert_eq!(result, expected);
+    }
+}
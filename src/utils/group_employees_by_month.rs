@@ -1,26 +1,28 @@
 use std::collections::BTreeMap;
 
+use chrono_tz::Tz;
+
 use crate::models::Employee;
 
 use super::{start_of_month::start_of_month, EmployeesByMonth};
 
-pub fn group_employees_by_month(employees: Vec<Employee>) -> EmployeesByMonth {
+/// Months follow the calendar of `tz`, so someone who joined on the evening
+/// of the 31st in Buenos Aires stays in that month.
+pub fn group_employees_by_month(employees: Vec<Employee>, tz: Tz) -> EmployeesByMonth {
     let mut employees_by_month: EmployeesByMonth = BTreeMap::new();
 
     for employee in employees {
-        let id = employee.id.clone();
-        let ts = employee.join_date.and_utc().timestamp();
-
-        match employees_by_month.get(&start_of_month(ts)) {
-            Some(employees) => {
-                let mut employees = employees.clone();
-                employees.push(id);
-                employees_by_month.insert(start_of_month(ts), employees);
-            }
-            None => {
-                employees_by_month.insert(start_of_month(ts), vec![id]);
-            }
-        }
+        let ts = employee
+            .join_date
+            .with_timezone(&tz)
+            .naive_local()
+            .and_utc()
+            .timestamp();
+
+        employees_by_month
+            .entry(start_of_month(ts))
+            .or_default()
+            .push(employee);
     }
     employees_by_month
 }
@@ -29,27 +31,37 @@ pub fn group_employees_by_month(employees: Vec<Employee>) -> EmployeesByMonth {
 mod test_group_employees_by_month {
     use std::collections::BTreeMap;
 
+    use chrono_tz::{America, Tz};
+
     use super::group_employees_by_month;
+    use crate::models::test_employee;
 
     #[test]
     fn test_group_employees_by_month() {
         let ts0_2024 = 1706745600; // 2024-02-01 00:00:00 UTC-0
         let ts0_2030 = 1906502400; // 2030-06-01 00:00:00 UTC-0
 
-        let (ts1, employee1) = (1707745600, "GHI789"); // 2024-02-12 UTC-0
-        let (ts2, employee2) = (1708048800, "ABC123"); // 2024-02-16 UTC-0
-        let (ts3, employee3) = (1908048800, "DEF456"); // 2030-06-18 UTC-0
-        let employees: Vec<(i64, String)> = vec![
-            (ts1, employee1.to_string()),
-            (ts2, employee2.to_string()),
-            (ts3, employee3.to_string()),
-        ];
-        // let result = group_employees_by_month(employees);
+        let employee1 = test_employee("GHI789", 1707745600); // 2024-02-12 UTC-0
+        let employee2 = test_employee("ABC123", 1708048800); // 2024-02-16 UTC-0
+        let employee3 = test_employee("DEF456", 1908048800); // 2030-06-18 UTC-0
+        let employees = vec![employee1.clone(), employee2.clone(), employee3.clone()];
+        let result = group_employees_by_month(employees, Tz::UTC);
 
         let mut expected = BTreeMap::new();
-        expected.insert(ts0_2024, vec![employee1.to_string(), employee2.to_string()]);
-        expected.insert(ts0_2030, vec![employee3.to_string()]);
+        expected.insert(ts0_2024, vec![employee1, employee2]);
+        expected.insert(ts0_2030, vec![employee3]);
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_group_by_the_month_of_the_timezone() {
+        let ts0_mar_2024 = 1709251200; // 2024-03-01 00:00:00 UTC-0
+        let employee = test_employee("GHI789", 1711933200); // 2024-04-01 01:00:00 UTC-0
+
+        let result =
+            group_employees_by_month(vec![employee.clone()], America::Argentina::Buenos_Aires);
 
-        // assert_eq!(result, expected);
+        assert_eq!(result, BTreeMap::from([(ts0_mar_2024, vec![employee])]));
     }
 }
@@ -1,4 +1,4 @@
-use chrono::{Local, NaiveDate};
+use chrono::NaiveDate;
 
 use super::ParseDateStrError;
 
@@ -7,13 +7,15 @@ pub fn last_day_of_month(year: i32, month: u32) -> Result<NaiveDate, ParseDateSt
         return Err(ParseDateStrError::DatePart(month.to_string()));
     }
 
-    let now = Local::now().date_naive();
-    let next_jan_first = NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap_or(now);
+    let next_month = match month {
+        12 => year.checked_add(1).map(|year| (year, 1)),
+        _ => Some((year, month + 1)),
+    };
 
-    Ok(NaiveDate::from_ymd_opt(year, month + 1, 1)
-        .unwrap_or(next_jan_first)
-        .pred_opt()
-        .unwrap())
+    next_month
+        .and_then(|(year, month)| NaiveDate::from_ymd_opt(year, month, 1))
+        .and_then(|first_day| first_day.pred_opt())
+        .ok_or_else(|| ParseDateStrError::DatePart(year.to_string()))
 }
 
 #[cfg(test)]
@@ -37,6 +39,11 @@ mod test_last_day_of_month {
         }
     }
 
+    #[test]
+    fn should_fail_for_years_out_of_range() {
+        assert!(last_day_of_month(i32::MAX, 12).is_err());
+    }
+
     #[test]
     fn returns_none_if_month_is_invalid() {
         let invalid_months = vec![0, 13];
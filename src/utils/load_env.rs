@@ -5,13 +5,23 @@ const ENV_VAR_NAMES: [&str; 8] = [
     "APP_ADDRESS",
     "APP_PORT",
     "APP_BASE_ROUTE",
-    "SLACK_TOKEN",
-    "SLACK_API_APP_ID",
+    "SLACK_SIGNING_SECRET",
+    "SLACK_BOT_TOKEN",
     "REDIS_HOSTNAME",
     "REDIS_PASSWORD",
     "REDIS_URI_SCHEME",
 ];
 
+/// Connection URL built from `REDIS_URI_SCHEME`, `REDIS_PASSWORD` and `REDIS_HOSTNAME`.
+pub fn redis_url() -> String {
+    format!(
+        "{}://:{}@{}",
+        env::var("REDIS_URI_SCHEME").expect("REDIS_URI_SCHEME must be set"),
+        env::var("REDIS_PASSWORD").expect("REDIS_PASSWORD must be set"),
+        env::var("REDIS_HOSTNAME").expect("REDIS_HOSTNAME must be set"),
+    )
+}
+
 pub fn load_env() {
     fn validate_env_vars() {
         for env_var in ENV_VAR_NAMES.iter() {
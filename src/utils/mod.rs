@@ -1,24 +1,55 @@
+pub mod group_employees_by_country;
 pub mod group_employees_by_month;
 pub mod last_day_of_month;
 pub mod load_env;
+pub mod paginate_employees;
+pub mod parse_channel;
 pub mod parse_date_str;
 pub mod parse_interval;
+pub mod parse_mention;
+pub mod parse_options;
+pub mod parse_relative_date;
+pub mod response_blocks;
 pub mod response_templates;
 pub mod start_of_month;
 
 use std::collections::BTreeMap;
 
+use crate::models::Employee;
+
 pub enum DateRound {
     Ceil,
     Floor,
 }
 
-pub type EmployeesByMonth = BTreeMap<i64, Vec<String>>;
+/// Month names as shown in answers, and understood in dates like "enero 2024".
+pub const SPANISH_MONTHS: [&str; 12] = [
+    "Enero",
+    "Febrero",
+    "Marzo",
+    "Abril",
+    "Mayo",
+    "Junio",
+    "Julio",
+    "Agosto",
+    "Septiembre",
+    "Octubre",
+    "Noviembre",
+    "Diciembre",
+];
+
+/// Keyed by the first second of each month, written as a UTC timestamp
+/// whatever timezone the months follow.
+pub type EmployeesByMonth = BTreeMap<i64, Vec<Employee>>;
 
 #[derive(Debug)]
 pub enum ParseDateStrError {
     DatePart(String),
     Date(String),
     Interval(String),
+    /// The start and end dates, as dd/mm/yyyy, of a range that ends before
+    /// it starts.
+    ReversedInterval(String, String),
+    InvalidOption(String),
     NoDate,
 }
@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+
+use super::EmployeesByMonth;
+
+/// Splits employees, most recent month first, into pages of `page_size`.
+/// Returns the employees of `page` still grouped by month, and how many pages
+/// there are. An empty list is a single, empty page.
+pub fn paginate_employees(
+    employees_by_month: EmployeesByMonth,
+    page: usize,
+    page_size: usize,
+) -> (EmployeesByMonth, usize) {
+    let total: usize = employees_by_month.values().map(Vec::len).sum();
+    let total_pages = total.div_ceil(page_size).max(1);
+
+    let mut page_by_month: EmployeesByMonth = BTreeMap::new();
+    let employees = employees_by_month
+        .into_iter()
+        .rev()
+        .flat_map(|(month, employees)| employees.into_iter().map(move |e| (month, e)))
+        .skip(page * page_size)
+        .take(page_size);
+    for (month, employee) in employees {
+        page_by_month.entry(month).or_default().push(employee);
+    }
+
+    (page_by_month, total_pages)
+}
+
+#[cfg(test)]
+mod test_paginate_employees {
+    use std::collections::BTreeMap;
+
+    use super::paginate_employees;
+    use crate::models::test_employee;
+
+    #[test]
+    fn should_start_with_the_most_recent_month() {
+        let jan = test_employee("JAN1", 1704067200); // 2024-01-01 UTC-0
+        let feb1 = test_employee("FEB1", 1707745600); // 2024-02-12 UTC-0
+        let feb2 = test_employee("FEB2", 1708048800); // 2024-02-16 UTC-0
+
+        let mut employees_by_month = BTreeMap::new();
+        employees_by_month.insert(1704067200, vec![jan.clone()]);
+        employees_by_month.insert(1706745600, vec![feb1.clone(), feb2.clone()]);
+
+        let (first, total_pages) = paginate_employees(employees_by_month.clone(), 0, 2);
+        assert_eq!(total_pages, 2);
+        assert_eq!(first, BTreeMap::from([(1706745600, vec![feb1, feb2])]));
+
+        let (second, _) = paginate_employees(employees_by_month.clone(), 1, 2);
+        assert_eq!(second, BTreeMap::from([(1704067200, vec![jan])]));
+
+        let (past_the_end, _) = paginate_employees(employees_by_month, 2, 2);
+        assert!(past_the_end.is_empty());
+    }
+
+    #[test]
+    fn should_have_one_page_when_empty() {
+        let (page, total_pages) = paginate_employees(BTreeMap::new(), 0, 10);
+
+        assert!(page.is_empty());
+        assert_eq!(total_pages, 1);
+    }
+}
@@ -0,0 +1,30 @@
+/// Extracts the channel id from a Slack channel link, escaped as `<#C123>` or
+/// `<#C123|general>` in slash command text.
+pub fn parse_channel(token: &str) -> Option<&str> {
+    let inner = token.strip_prefix("<#")?.strip_suffix('>')?;
+    let id = inner.split('|').next()?;
+
+    if id.is_empty() {
+        None
+    } else {
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod test_parse_channel {
+    use super::parse_channel;
+
+    #[test]
+    fn should_return_channel_id_of_escaped_links() {
+        assert_eq!(parse_channel("<#C123>"), Some("C123"));
+        assert_eq!(parse_channel("<#C123|bienvenidos>"), Some("C123"));
+    }
+
+    #[test]
+    fn should_return_none_for_anything_else() {
+        for token in ["", "C123", "#general", "<#>", "<@U123|juan>", "<#C123"] {
+            assert_eq!(parse_channel(token), None);
+        }
+    }
+}
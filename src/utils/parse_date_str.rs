@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
 
 use super::{last_day_of_month::last_day_of_month, DateRound, ParseDateStrError};
 
@@ -45,10 +45,7 @@ fn handle_month_year(
     match (year, month) {
         (Ok(year), Ok(month)) => {
             let day = match round {
-                DateRound::Ceil => match last_day_of_month(year, month).map(|d| d.day()) {
-                    Ok(day) => day,
-                    Err(e) => return Err(e),
-                },
+                DateRound::Ceil => last_day_of_month(year, month).map(|d| d.day())?,
                 DateRound::Floor => 1,
             };
             let d = NaiveDate::from_ymd_opt(year, month, day).map(|d| NaiveDateTime::new(d, time));
@@ -73,7 +70,7 @@ fn handle_year(year_str: &str, round: DateRound) -> Result<NaiveDateTime, ParseD
                 DateRound::Floor => 1,
             };
             let day = match round {
-                DateRound::Ceil => last_day_of_month(year, month).unwrap().day(),
+                DateRound::Ceil => last_day_of_month(year, month)?.day(),
                 DateRound::Floor => 1,
             };
             let d = NaiveDate::from_ymd_opt(year, month, day).map(|d| NaiveDateTime::new(d, time));
@@ -89,21 +86,91 @@ fn handle_year(year_str: &str, round: DateRound) -> Result<NaiveDateTime, ParseD
     }
 }
 
-fn time_by_date_round(round: &DateRound) -> NaiveTime {
+/// `2024-W07`: Monday to Sunday of that ISO week.
+fn handle_iso_week(
+    year_str: &str,
+    week_str: &str,
+    round: DateRound,
+) -> Result<NaiveDateTime, ParseDateStrError> {
+    let time = time_by_date_round(&round);
+    let (year, week) = (FromStr::from_str(year_str), FromStr::from_str(week_str));
+
+    match (year, week) {
+        (Ok(year), Ok(week)) => {
+            let weekday = match round {
+                DateRound::Ceil => Weekday::Sun,
+                DateRound::Floor => Weekday::Mon,
+            };
+            match NaiveDate::from_isoywd_opt(year, week, weekday) {
+                Some(d) => Ok(NaiveDateTime::new(d, time)),
+                None => Err(ParseDateStrError::Date(format!(
+                    "{}-W{}",
+                    year_str, week_str
+                ))),
+            }
+        }
+        (Err(_), _) => Err(ParseDateStrError::DatePart(year_str.to_string())),
+        (_, Err(_)) => Err(ParseDateStrError::DatePart(week_str.to_string())),
+    }
+}
+
+/// `Q1/2024` or `T1/2024` (trimestre): January to March.
+fn handle_quarter(
+    quarter_str: &str,
+    year_str: &str,
+    round: DateRound,
+) -> Result<NaiveDateTime, ParseDateStrError> {
+    let quarter = match u32::from_str(&quarter_str[1..]) {
+        Ok(quarter) if (1..=4).contains(&quarter) => quarter,
+        _ => return Err(ParseDateStrError::DatePart(quarter_str.to_string())),
+    };
+    let month = match round {
+        DateRound::Ceil => quarter * 3,
+        DateRound::Floor => quarter * 3 - 2,
+    };
+
+    handle_month_year(&month.to_string(), year_str, round)
+}
+
+pub(super) fn time_by_date_round(round: &DateRound) -> NaiveTime {
     match round {
         DateRound::Ceil => NaiveTime::from_hms_opt(23, 59, 59).unwrap(),
         DateRound::Floor => NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
     }
 }
 
+fn is_quarter(part: &str) -> bool {
+    part.len() > 1 && part.starts_with(['q', 'Q', 't', 'T'])
+}
+
+/// ISO 8601: `2024-02-15`, `2024-02` and `2024-W07`.
+fn parse_iso_date_str(
+    date_str: &str,
+    round: DateRound,
+) -> Result<NaiveDateTime, ParseDateStrError> {
+    let date_parts = date_str.split('-').collect::<Vec<&str>>();
+
+    match date_parts.as_slice() {
+        [year, week] if week.starts_with(['w', 'W']) => handle_iso_week(year, &week[1..], round),
+        [year, month, day] => handle_full_date(day, month, year, round),
+        [year, month] => handle_month_year(month, year, round),
+        _ => Err(ParseDateStrError::Date(date_str.to_string())),
+    }
+}
+
 pub fn parse_date_str(
     date_str: &str,
     round: DateRound,
 ) -> Result<NaiveDateTime, ParseDateStrError> {
+    if date_str.contains('-') {
+        return parse_iso_date_str(date_str, round);
+    }
+
     let date_parts = date_str.split('/').collect::<Vec<&str>>();
 
     match date_parts.len() {
         3 => handle_full_date(date_parts[0], date_parts[1], date_parts[2], round),
+        2 if is_quarter(date_parts[0]) => handle_quarter(date_parts[0], date_parts[1], round),
         2 => handle_month_year(date_parts[0], date_parts[1], round),
         1 => handle_year(date_parts[0], round),
         _ => Err(ParseDateStrError::Date(date_str.to_string())),
@@ -196,6 +263,59 @@ mod test_parse_date_str {
         assert_eq!(res_eod.unwrap(), eod);
     }
 
+    #[test]
+    fn should_round_iso_dates_weeks_and_quarters() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        for (date_str, floor, ceil) in [
+            ("2024-02-15", date(2024, 2, 15), date(2024, 2, 15)),
+            ("2024-02", date(2024, 2, 1), date(2024, 2, 29)),
+            ("2024-W07", date(2024, 2, 12), date(2024, 2, 18)),
+            ("2024-w01", date(2024, 1, 1), date(2024, 1, 7)),
+            ("2021-W01", date(2021, 1, 4), date(2021, 1, 10)), // ISO year starts on Monday
+            ("2020-W53", date(2020, 12, 28), date(2021, 1, 3)),
+            ("Q1/2024", date(2024, 1, 1), date(2024, 3, 31)),
+            ("q2/2024", date(2024, 4, 1), date(2024, 6, 30)),
+            ("T3/2024", date(2024, 7, 1), date(2024, 9, 30)),
+            ("t4/2023", date(2023, 10, 1), date(2023, 12, 31)),
+        ] {
+            assert_eq!(
+                parse_date_str(date_str, DateRound::Floor).unwrap(),
+                bod_hms_opt(floor).unwrap(),
+                "{}",
+                date_str
+            );
+            assert_eq!(
+                parse_date_str(date_str, DateRound::Ceil).unwrap(),
+                eod_hms_opt(ceil).unwrap(),
+                "{}",
+                date_str
+            );
+        }
+    }
+
+    #[test]
+    fn should_err_on_invalid_iso_dates_weeks_and_quarters() {
+        let invalid_inputs = [
+            "2024-02-30", // feb is never 30
+            "2024-13",    // there are 12 months
+            "2024-W00",   // weeks start at 1
+            "2021-W53",   // 2021 has 52 ISO weeks
+            "2024-Wx",    // not a week
+            "2024-02-15-1",
+            "-",
+            "Q0/2024", // quarters go from 1 to 4
+            "Q5/2024",
+            "T1/24x4",
+            "Qx/2024",
+        ];
+
+        for input in invalid_inputs {
+            let res = parse_date_str(input, DateRound::Floor);
+            assert!(res.is_err(), "{}", input);
+        }
+    }
+
     #[test]
     fn should_err_on_invalid_input() {
         let invalid_inputs = [
@@ -214,4 +334,12 @@ mod test_parse_date_str {
             assert!(res.is_err());
         }
     }
+
+    #[test]
+    fn should_err_on_years_out_of_range() {
+        for input in ["2147483647", "12/2147483647"] {
+            let res = parse_date_str(input, DateRound::Ceil);
+            assert!(res.is_err(), "{}", input);
+        }
+    }
 }
@@ -1,44 +1,122 @@
-use chrono::NaiveDateTime;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
 
-use super::{parse_date_str::parse_date_str, DateRound, ParseDateStrError};
+use super::{
+    parse_date_str::{parse_date_str, time_by_date_round},
+    parse_relative_date::parse_relative_date,
+    DateRound, ParseDateStrError,
+};
 
+/// The start and end of the dates in a command, read as days on the clocks of
+/// `tz`. The start is `None` for "hasta <fecha>", meaning since the first
+/// employee joined.
 pub fn parse_interval(
     command_text: &str,
-) -> Result<(NaiveDateTime, NaiveDateTime), ParseDateStrError> {
+    tz: Tz,
+) -> Result<(Option<DateTime<Utc>>, DateTime<Utc>), ParseDateStrError> {
+    let today = Utc::now().with_timezone(&tz).date_naive();
+    let (from, to) = parse_interval_at(command_text, today)?;
+
+    Ok((
+        from.map(|from| local_to_utc(from, tz, DateRound::Floor)),
+        local_to_utc(to, tz, DateRound::Ceil),
+    ))
+}
+
+/// When `date` happens in `tz`. If it happens twice as DST ends, the first
+/// time starts a day and the second one ends it. If it doesn't happen because
+/// DST starts at midnight, the day starts when the clocks jump.
+pub fn local_to_utc(date: NaiveDateTime, tz: Tz, round: DateRound) -> DateTime<Utc> {
+    let local = tz.from_local_datetime(&date);
+    let local = match round {
+        DateRound::Floor => local.earliest(),
+        DateRound::Ceil => local.latest(),
+    };
+
+    local.map(|d| d.with_timezone(&Utc)).unwrap_or_else(|| {
+        let offset = tz.offset_from_utc_datetime(&date).fix().local_minus_utc();
+        (date - Duration::seconds(offset as i64)).and_utc()
+    })
+}
+
+/// `parse_interval` with relative dates like "este mes", and the end of
+/// "desde <fecha>", resolved from `today`.
+pub fn parse_interval_at(
+    command_text: &str,
+    today: NaiveDate,
+) -> Result<(Option<NaiveDateTime>, NaiveDateTime), ParseDateStrError> {
     let lower = command_text.to_lowercase();
-    let v = lower
-        .trim()
-        .split(' ')
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<&str>>();
-
-    match v.len() {
-        0 => Err(ParseDateStrError::NoDate),
-        1 => match (
-            parse_date_str(v[0], DateRound::Floor),
-            parse_date_str(v[0], DateRound::Ceil),
-        ) {
-            (Ok(from), Ok(to)) => Ok((from, to)),
-            (Err(e), _) => Err(e),
-            (_, Err(e)) => Err(e),
+    let v = lower.split_whitespace().collect::<Vec<&str>>();
+
+    let (from, to) = match v.as_slice() {
+        ["hasta", to @ ..] => (None, parse_bound(to, DateRound::Ceil, today)?),
+        ["desde", rest @ ..] => match rest.iter().position(|word| *word == "hasta") {
+            Some(i) => (
+                Some(parse_bound(&rest[..i], DateRound::Floor, today)?),
+                parse_bound(&rest[i + 1..], DateRound::Ceil, today)?,
+            ),
+            None => (
+                Some(parse_bound(rest, DateRound::Floor, today)?),
+                today.and_time(time_by_date_round(&DateRound::Ceil)),
+            ),
         },
-        2 => {
-            let parsed_from = parse_date_str(v[0], DateRound::Floor);
-            let parsed_to = parse_date_str(v[1], DateRound::Ceil);
-
-            match (parsed_from, parsed_to) {
-                (Ok(from), Ok(to)) => Ok((from, to)),
-                (Err(e), _) => Err(e),
-                (_, Err(e)) => Err(e),
-            }
-        }
-        _ => Err(ParseDateStrError::Interval(command_text.to_string())),
+        _ => match parse_relative_date(command_text, today) {
+            Some((first, last)) => (
+                Some(first.and_time(time_by_date_round(&DateRound::Floor))),
+                last.and_time(time_by_date_round(&DateRound::Ceil)),
+            ),
+            None => match v.as_slice() {
+                [] => return Err(ParseDateStrError::NoDate),
+                [date] => (
+                    Some(parse_date_str(date, DateRound::Floor)?),
+                    parse_date_str(date, DateRound::Ceil)?,
+                ),
+                [from, to] => (
+                    Some(parse_date_str(from, DateRound::Floor)?),
+                    parse_date_str(to, DateRound::Ceil)?,
+                ),
+                _ => return Err(ParseDateStrError::Interval(command_text.to_string())),
+            },
+        },
+    };
+
+    match from {
+        Some(from) if from > to => Err(ParseDateStrError::ReversedInterval(
+            from.format("%d/%m/%Y").to_string(),
+            to.format("%d/%m/%Y").to_string(),
+        )),
+        _ => Ok((from, to)),
+    }
+}
+
+/// One end of a "desde"/"hasta" interval: a relative period or a single date.
+fn parse_bound(
+    words: &[&str],
+    round: DateRound,
+    today: NaiveDate,
+) -> Result<NaiveDateTime, ParseDateStrError> {
+    let time = time_by_date_round(&round);
+    if let Some((first, last)) = parse_relative_date(&words.join(" "), today) {
+        return Ok(match round {
+            DateRound::Floor => first.and_time(time),
+            DateRound::Ceil => last.and_time(time),
+        });
+    }
+
+    match words {
+        [] => Err(ParseDateStrError::NoDate),
+        [date] => parse_date_str(date, round),
+        _ => Err(ParseDateStrError::Interval(words.join(" "))),
     }
 }
 
 #[cfg(test)]
 mod test_parse_interval {
-    use super::parse_interval;
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use chrono_tz::{America, Tz};
+
+    use super::{parse_interval, parse_interval_at};
+    use crate::utils::ParseDateStrError;
 
     #[test]
     fn should_return_from_to_today_tuple_with_one_date() {
@@ -47,10 +125,10 @@ mod test_parse_interval {
         let param_date_eod = param_date.and_hms_opt(23, 59, 59).unwrap();
         let param_date_bod = param_date.and_hms_opt(0, 0, 0).unwrap();
 
-        let (from, to) = parse_interval(param).unwrap();
+        let (from, to) = parse_interval(param, Tz::UTC).unwrap();
 
-        assert_eq!(from, param_date_bod);
-        assert_eq!(to, param_date_eod);
+        assert_eq!(from, Some(param_date_bod.and_utc()));
+        assert_eq!(to, param_date_eod.and_utc());
     }
 
     #[test]
@@ -62,9 +140,140 @@ mod test_parse_interval {
         let from_date_bod = from_date.and_hms_opt(0, 0, 0).unwrap();
         let to_date_eod = to_date.and_hms_opt(23, 59, 59).unwrap();
 
-        let (from, to) = parse_interval(format!("{} {}", from, to).as_str()).unwrap();
+        let (from, to) = parse_interval(format!("{} {}", from, to).as_str(), Tz::UTC).unwrap();
+
+        assert_eq!(from, Some(from_date_bod.and_utc()));
+        assert_eq!(to, to_date_eod.and_utc());
+    }
+
+    #[test]
+    fn should_read_dates_in_the_users_timezone() {
+        let (from, to) = parse_interval("31/03/2024", America::Argentina::Buenos_Aires).unwrap();
+
+        assert_eq!(
+            from,
+            Some(Utc.with_ymd_and_hms(2024, 3, 31, 3, 0, 0).unwrap())
+        );
+        assert_eq!(to, Utc.with_ymd_and_hms(2024, 4, 1, 2, 59, 59).unwrap());
+    }
 
-        assert_eq!(from, from_date_bod);
-        assert_eq!(to, to_date_eod);
+    #[test]
+    fn should_start_days_without_midnight_when_clocks_jump() {
+        // Chile moved from UTC-4 to UTC-3 at midnight on 08/09/2024.
+        let (from, to) = parse_interval("08/09/2024", America::Santiago).unwrap();
+
+        assert_eq!(
+            from,
+            Some(Utc.with_ymd_and_hms(2024, 9, 8, 4, 0, 0).unwrap())
+        );
+        assert_eq!(to, Utc.with_ymd_and_hms(2024, 9, 9, 2, 59, 59).unwrap());
+    }
+
+    #[test]
+    fn should_floor_and_ceil_relative_dates() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap();
+        let day = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+
+        for (text, from, to) in [
+            ("este mes", day(3, 1), day(3, 31)),
+            ("ayer", day(3, 13), day(3, 13)),
+            ("enero 2024", day(1, 1), day(1, 31)),
+            ("01/2024 02/2024", day(1, 1), day(2, 29)),
+            ("Q1/2024 T2/2024", day(1, 1), day(6, 30)),
+            ("2024-02-01 2024-W10", day(2, 1), day(3, 10)),
+        ] {
+            assert_eq!(
+                parse_interval_at(text, today).unwrap(),
+                (
+                    Some(from.and_hms_opt(0, 0, 0).unwrap()),
+                    to.and_hms_opt(23, 59, 59).unwrap()
+                ),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn should_leave_desde_and_hasta_open() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap();
+        let bod = |year, month, day| {
+            NaiveDate::from_ymd_opt(year, month, day)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        };
+        let eod = |year, month, day| {
+            NaiveDate::from_ymd_opt(year, month, day)
+                .unwrap()
+                .and_hms_opt(23, 59, 59)
+                .unwrap()
+        };
+
+        for (text, from, to) in [
+            ("desde 03/2023", Some(bod(2023, 3, 1)), eod(2024, 3, 14)),
+            (
+                "Desde el mes pasado",
+                Some(bod(2024, 2, 1)),
+                eod(2024, 3, 14),
+            ),
+            ("hasta 2023", None, eod(2023, 12, 31)),
+            ("hasta enero de 2024", None, eod(2024, 1, 31)),
+            (
+                "desde 2022 hasta Q1/2023",
+                Some(bod(2022, 1, 1)),
+                eod(2023, 3, 31),
+            ),
+            (
+                "desde enero hasta ayer",
+                Some(bod(2024, 1, 1)),
+                eod(2024, 3, 13),
+            ),
+        ] {
+            assert_eq!(
+                parse_interval_at(text, today).unwrap(),
+                (from, to),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn should_reject_intervals_that_end_before_they_start() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap();
+
+        for (text, from, to) in [
+            ("02/2024 01/2024", "01/02/2024", "31/01/2024"),
+            ("desde 2025", "01/01/2025", "14/03/2024"),
+            ("desde 2024 hasta 2023", "01/01/2024", "31/12/2023"),
+        ] {
+            match parse_interval_at(text, today) {
+                Err(ParseDateStrError::ReversedInterval(start, end)) => {
+                    assert_eq!((start.as_str(), end.as_str()), (from, to), "{}", text)
+                }
+                other => panic!("{}: {:?}", text, other),
+            }
+        }
+    }
+
+    #[test]
+    fn should_need_a_date_after_desde_and_hasta() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap();
+
+        for text in ["desde", "hasta", "desde 2024 hasta"] {
+            assert!(
+                matches!(
+                    parse_interval_at(text, today),
+                    Err(ParseDateStrError::NoDate)
+                ),
+                "{}",
+                text
+            );
+        }
+        assert!(matches!(
+            parse_interval_at("hasta 2023 2024", today),
+            Err(ParseDateStrError::Interval(_))
+        ));
     }
 }
@@ -0,0 +1,30 @@
+/// Extracts the user id from a Slack mention, escaped as `<@U123>` or
+/// `<@U123|name>` in slash command text.
+pub fn parse_mention(token: &str) -> Option<&str> {
+    let inner = token.strip_prefix("<@")?.strip_suffix('>')?;
+    let id = inner.split('|').next()?;
+
+    if id.is_empty() {
+        None
+    } else {
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod test_parse_mention {
+    use super::parse_mention;
+
+    #[test]
+    fn should_return_user_id_of_escaped_mentions() {
+        assert_eq!(parse_mention("<@U123>"), Some("U123"));
+        assert_eq!(parse_mention("<@U123|juan>"), Some("U123"));
+    }
+
+    #[test]
+    fn should_return_none_for_anything_else() {
+        for token in ["", "U123", "@juan", "<@>", "<#C123|general>", "<@U123"] {
+            assert_eq!(parse_mention(token), None);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use super::ParseDateStrError;
+
+/// `clave:valor` tokens accepted by `/nuevos` next to the dates.
+#[derive(Debug, Default, PartialEq)]
+pub struct NuevosOptions {
+    /// `bajas:si` also lists people who already left.
+    pub include_inactive: bool,
+    /// `publico:si` shows the answer to the whole channel.
+    pub in_channel: bool,
+    /// `pais:argentina pais:costa_rica` only lists people from those
+    /// countries, lowercased and with spaces like `Employee.country`.
+    pub countries: Vec<String>,
+    /// `agrupar:pais` splits each month by country.
+    pub group_by_country: bool,
+}
+
+fn parse_yes_no(token: &str, value: &str) -> Result<bool, ParseDateStrError> {
+    match value {
+        "si" | "sí" => Ok(true),
+        "no" => Ok(false),
+        _ => Err(ParseDateStrError::InvalidOption(token.to_string())),
+    }
+}
+
+/// Splits the command text into the date part and the options.
+pub fn parse_options(command_text: &str) -> Result<(String, NuevosOptions), ParseDateStrError> {
+    let mut options = NuevosOptions::default();
+    let mut dates = vec![];
+
+    for token in command_text.split(' ').filter(|s| !s.is_empty()) {
+        match token.to_lowercase().split_once(':') {
+            Some(("bajas", value)) => options.include_inactive = parse_yes_no(token, value)?,
+            Some(("publico" | "público", value)) => {
+                options.in_channel = parse_yes_no(token, value)?
+            }
+            Some(("pais" | "país", value)) if !value.is_empty() => {
+                options.countries.push(value.replace('_', " "))
+            }
+            Some(("agrupar", "pais" | "país")) => options.group_by_country = true,
+            Some(_) => return Err(ParseDateStrError::InvalidOption(token.to_string())),
+            None => dates.push(token),
+        }
+    }
+
+    Ok((dates.join(" "), options))
+}
+
+#[cfg(test)]
+mod test_parse_options {
+    use super::{parse_options, NuevosOptions};
+
+    #[test]
+    fn should_split_dates_and_options() {
+        let (dates, options) = parse_options("01/2024 bajas:si 02/2024 publico:si").unwrap();
+
+        assert_eq!(dates, "01/2024 02/2024");
+        assert_eq!(
+            options,
+            NuevosOptions {
+                include_inactive: true,
+                in_channel: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn should_default_options_when_absent() {
+        let (dates, options) = parse_options("2024").unwrap();
+
+        assert_eq!(dates, "2024");
+        assert_eq!(options, NuevosOptions::default());
+    }
+
+    #[test]
+    fn should_collect_countries_and_grouping() {
+        let (dates, options) =
+            parse_options("2024 País:Argentina pais:costa_rica agrupar:pais").unwrap();
+
+        assert_eq!(dates, "2024");
+        assert_eq!(
+            options,
+            NuevosOptions {
+                countries: vec!["argentina".to_string(), "costa rica".to_string()],
+                group_by_country: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn should_err_on_unknown_options() {
+        for text in [
+            "2024 bajas:quizas",
+            "2024 publico:todos",
+            "2024 pais:",
+            "2024 agrupar:mes",
+            "2024 color:rojo",
+        ] {
+            assert!(parse_options(text).is_err());
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+use super::{last_day_of_month::last_day_of_month, SPANISH_MONTHS};
+
+/// Lowercases and drops accents and leading articles, so "Últimos 30 días"
+/// and "el año pasado" read like "ultimos 30 dias" and "ano pasado".
+fn normalize(text: &str) -> Vec<String> {
+    let text = text
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'á' => 'a',
+            'é' => 'e',
+            'í' => 'i',
+            'ó' => 'o',
+            'ú' | 'ü' => 'u',
+            'ñ' => 'n',
+            c => c,
+        })
+        .collect::<String>();
+
+    text.split_whitespace()
+        .skip_while(|word| matches!(*word, "el" | "la" | "los" | "las"))
+        .map(String::from)
+        .collect()
+}
+
+fn month_number(name: &str) -> Option<u32> {
+    SPANISH_MONTHS
+        .iter()
+        .position(|month| month.to_lowercase() == name)
+        .map(|i| i as u32 + 1)
+}
+
+fn month(year: i32, month: u32) -> Option<(NaiveDate, NaiveDate)> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let last = last_day_of_month(year, month).ok()?;
+    Some((first, last))
+}
+
+fn year(year: i32) -> Option<(NaiveDate, NaiveDate)> {
+    Some((
+        NaiveDate::from_ymd_opt(year, 1, 1)?,
+        NaiveDate::from_ymd_opt(year, 12, 31)?,
+    ))
+}
+
+/// Monday to Sunday around `day`.
+fn week(day: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    let monday = day - Days::new(day.weekday().num_days_from_monday() as u64);
+    Some((monday, monday + Days::new(6)))
+}
+
+/// The last `count` days, weeks or months, counting today.
+fn last(count: &str, unit: &str, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    let count = count.parse::<u32>().ok().filter(|count| *count > 0)?;
+    let day_after_start = match unit {
+        "dia" | "dias" => today.checked_sub_days(Days::new(count as u64)),
+        "semana" | "semanas" => today.checked_sub_days(Days::new(7 * count as u64)),
+        "mes" | "meses" => today.checked_sub_months(Months::new(count)),
+        _ => None,
+    }?;
+    Some((day_after_start.succ_opt()?, today))
+}
+
+/// The first and last day of a period described in Spanish relative to
+/// `today`: "hoy", "ayer", "esta semana", "semana pasada", "este mes",
+/// "mes pasado", "este año", "año pasado", "ultimos 30 dias" (or semanas and
+/// meses), "enero" (of this year) and "enero 2024" or "enero de 2024".
+pub fn parse_relative_date(text: &str, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    let words = normalize(text);
+    let words = words.iter().map(String::as_str).collect::<Vec<&str>>();
+
+    match words.as_slice() {
+        ["hoy"] => Some((today, today)),
+        ["ayer"] => today.pred_opt().map(|day| (day, day)),
+        ["esta", "semana"] => week(today),
+        ["semana", "pasada"] => week(today - Days::new(7)),
+        ["este", "mes"] => month(today.year(), today.month()),
+        ["mes", "pasado"] => {
+            let day = today.checked_sub_months(Months::new(1))?;
+            month(day.year(), day.month())
+        }
+        ["este", "ano"] => year(today.year()),
+        ["ano", "pasado"] => year(today.year() - 1),
+        ["ultimo" | "ultima" | "ultimos" | "ultimas", count, unit] => last(count, unit, today),
+        [name] => month(today.year(), month_number(name)?),
+        [name, year] | [name, "de", year] => month(year.parse().ok()?, month_number(name)?),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test_parse_relative_date {
+    use chrono::NaiveDate;
+
+    use super::parse_relative_date;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn should_resolve_periods_relative_to_today() {
+        let today = date(2024, 3, 14); // a Thursday
+
+        for (text, first, last) in [
+            ("hoy", date(2024, 3, 14), date(2024, 3, 14)),
+            ("ayer", date(2024, 3, 13), date(2024, 3, 13)),
+            ("esta semana", date(2024, 3, 11), date(2024, 3, 17)),
+            ("semana pasada", date(2024, 3, 4), date(2024, 3, 10)),
+            ("la semana pasada", date(2024, 3, 4), date(2024, 3, 10)),
+            ("este mes", date(2024, 3, 1), date(2024, 3, 31)),
+            ("mes pasado", date(2024, 2, 1), date(2024, 2, 29)),
+            ("el mes pasado", date(2024, 2, 1), date(2024, 2, 29)),
+            ("este año", date(2024, 1, 1), date(2024, 12, 31)),
+            ("el año pasado", date(2023, 1, 1), date(2023, 12, 31)),
+            ("ultimos 30 dias", date(2024, 2, 14), date(2024, 3, 14)),
+            ("Últimos 7 días", date(2024, 3, 8), date(2024, 3, 14)),
+            ("ultimo 1 dia", date(2024, 3, 14), date(2024, 3, 14)),
+            ("ultimas 2 semanas", date(2024, 3, 1), date(2024, 3, 14)),
+            ("ultimos 3 meses", date(2023, 12, 15), date(2024, 3, 14)),
+            ("enero", date(2024, 1, 1), date(2024, 1, 31)),
+            ("enero 2023", date(2023, 1, 1), date(2023, 1, 31)),
+            ("Febrero de 2023", date(2023, 2, 1), date(2023, 2, 28)),
+            ("septiembre 2024", date(2024, 9, 1), date(2024, 9, 30)),
+        ] {
+            assert_eq!(
+                parse_relative_date(text, today),
+                Some((first, last)),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn should_cross_year_boundaries() {
+        let today = date(2024, 1, 3); // a Wednesday
+
+        for (text, first, last) in [
+            ("ayer", date(2024, 1, 2), date(2024, 1, 2)),
+            ("semana pasada", date(2023, 12, 25), date(2023, 12, 31)),
+            ("mes pasado", date(2023, 12, 1), date(2023, 12, 31)),
+        ] {
+            assert_eq!(
+                parse_relative_date(text, today),
+                Some((first, last)),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn should_ignore_anything_else() {
+        let today = date(2024, 3, 14);
+
+        for text in [
+            "",
+            "2024",
+            "01/2024",
+            "ultimos 0 dias",
+            "ultimos x dias",
+            "ultimos 3 anos",
+            "enero 20x4",
+            "semana que viene",
+            "enero 2023 marzo 2023",
+        ] {
+            assert_eq!(parse_relative_date(text, today), None, "{}", text);
+        }
+    }
+}
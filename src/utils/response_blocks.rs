@@ -0,0 +1,304 @@
+use chrono::{DateTime, NaiveDate};
+use chrono_tz::Tz;
+
+use super::group_employees_by_country::group_employees_by_country;
+use super::response_templates::{country_label, format_country, format_date, format_month, tag};
+use super::EmployeesByMonth;
+use crate::models::Employee;
+use crate::slack_api::blocks::{Block, Element, Modal, Text};
+use crate::slash_command::nuevos::{
+    COUNTRY_BLOCK, FROM_BLOCK, INPUT_ACTION, RANGE_CALLBACK, TO_BLOCK,
+};
+
+fn employee_blocks(employee: &Employee, tz: Tz) -> [Block; 2] {
+    let mut details = vec![
+        country_label(employee.country.as_deref()),
+        format!(
+            "Entró el {}",
+            format_date(employee.join_date.with_timezone(&tz).date_naive())
+        ),
+    ];
+    if let Some(left_date) = employee.left_date {
+        details.push(format!("Se fue el {}", format_date(left_date)));
+    }
+
+    [
+        Block::section(format!("{} {}", tag(&employee.id), employee.full_name)),
+        Block::context(details),
+    ]
+}
+
+/// Block Kit version of `new_employees_template`: a header per month, most
+/// recent first, and a mention with its details per employee. With
+/// `group_by_country` each country gets a title within its month. Dates are
+/// shown on the clocks of `tz`.
+pub fn new_employees_blocks(
+    from_ts: i64,
+    to_ts: i64,
+    employees_by_month: &EmployeesByMonth,
+    group_by_country: bool,
+    tz: Tz,
+) -> Vec<Block> {
+    let [from, to] = [from_ts, to_ts].map(|d| {
+        DateTime::from_timestamp(d, 0)
+            .map(|d| format_date(d.with_timezone(&tz).date_naive()))
+            .unwrap_or_default()
+    });
+
+    let mut blocks = vec![Block::section(format!(
+        "Los que entraron desde el *{}* hasta el *{}*:",
+        from, to
+    ))];
+    if employees_by_month.is_empty() {
+        blocks.push(Block::context(vec![
+            "No entró nadie en ese período.".to_string()
+        ]));
+    }
+
+    for (&month, employees) in employees_by_month.iter().rev() {
+        blocks.push(Block::Divider);
+        blocks.push(Block::header(format_month(month)));
+        if group_by_country {
+            for (country, employees) in group_employees_by_country(employees) {
+                blocks.push(Block::section(format!("*{}*", country_label(country))));
+                blocks.extend(
+                    employees
+                        .into_iter()
+                        .flat_map(|employee| employee_blocks(employee, tz)),
+                );
+            }
+        } else {
+            blocks.extend(
+                employees
+                    .iter()
+                    .flat_map(|employee| employee_blocks(employee, tz)),
+            );
+        }
+    }
+    blocks
+}
+
+/// "Anterior"/"Siguiente" buttons for `action_id`, whose values come from
+/// `value_for(page)`. Nothing when everything fits in one page.
+pub fn pagination_blocks(
+    page: usize,
+    total_pages: usize,
+    action_id: &str,
+    value_for: impl Fn(usize) -> String,
+) -> Vec<Block> {
+    if total_pages <= 1 {
+        return vec![];
+    }
+
+    let mut buttons = vec![];
+    if page > 0 {
+        buttons.push(Element::button("Anterior", action_id, value_for(page - 1)));
+    }
+    if page + 1 < total_pages {
+        buttons.push(Element::button("Siguiente", action_id, value_for(page + 1)));
+    }
+
+    vec![
+        Block::Divider,
+        Block::context(vec![format!("Página {} de {}", page + 1, total_pages)]),
+        Block::Actions { elements: buttons },
+    ]
+}
+
+/// The `/nuevos` form: two dates and, optionally, countries.
+pub fn date_range_modal(
+    private_metadata: String,
+    from: NaiveDate,
+    to: NaiveDate,
+    countries: &[String],
+) -> Modal {
+    let date_input = |block_id: &str, label: &str, date: NaiveDate| Block::Input {
+        block_id: block_id.to_string(),
+        label: Text::plain(label),
+        element: Element::datepicker(INPUT_ACTION, Some(date.format("%Y-%m-%d").to_string())),
+        optional: false,
+    };
+
+    Modal {
+        callback_id: RANGE_CALLBACK.to_string(),
+        title: Text::plain("Nuevos ingresos"),
+        submit: Text::plain("Buscar"),
+        close: Text::plain("Cancelar"),
+        private_metadata,
+        blocks: vec![
+            date_input(FROM_BLOCK, "Desde", from),
+            date_input(TO_BLOCK, "Hasta", to),
+            Block::Input {
+                block_id: COUNTRY_BLOCK.to_string(),
+                label: Text::plain("País"),
+                element: Element::PlainTextInput {
+                    action_id: INPUT_ACTION.to_string(),
+                    placeholder: Some(Text::plain("Argentina, Chile")),
+                    initial_value: (!countries.is_empty()).then(|| {
+                        countries
+                            .iter()
+                            .map(|country| format_country(country))
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    }),
+                },
+                optional: true,
+            },
+        ],
+    }
+}
+
+#[cfg(test)]
+mod test_new_employees_blocks {
+    use std::collections::BTreeMap;
+
+    use chrono_tz::{America, Tz};
+
+    use super::new_employees_blocks;
+    use crate::models::test_employee;
+    use crate::slack_api::blocks::Block;
+
+    #[test]
+    fn should_render_a_header_per_month_most_recent_first() {
+        let mut employee = test_employee("ABC123", 1708048800); // 2024-02-16 UTC-0
+        employee.country = Some("argentina".to_string());
+        let mut left = test_employee("DEF456", 1704067200); // 2024-01-01 UTC-0
+        left.left_date = Some(employee.join_date.date_naive());
+
+        let mut employees_by_month = BTreeMap::new();
+        employees_by_month.insert(1704067200, vec![left]);
+        employees_by_month.insert(1706745600, vec![employee]);
+
+        let blocks =
+            new_employees_blocks(1704067200, 1709251199, &employees_by_month, false, Tz::UTC);
+
+        assert_eq!(
+            blocks,
+            vec![
+                Block::section("Los que entraron desde el *01/01/2024* hasta el *29/02/2024*:"),
+                Block::Divider,
+                Block::header("Febrero 2024"),
+                Block::section("<@ABC123> ABC123"),
+                Block::context(vec![
+                    "Argentina".to_string(),
+                    "Entró el 16/02/2024".to_string()
+                ]),
+                Block::Divider,
+                Block::header("Enero 2024"),
+                Block::section("<@DEF456> DEF456"),
+                Block::context(vec![
+                    "Sin país".to_string(),
+                    "Entró el 01/01/2024".to_string(),
+                    "Se fue el 16/02/2024".to_string()
+                ]),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_title_countries_when_grouping() {
+        let mut chile = test_employee("ABC123", 1708048800); // 2024-02-16 UTC-0
+        chile.country = Some("chile".to_string());
+        let mut argentina = test_employee("DEF456", 1708048800);
+        argentina.country = Some("argentina".to_string());
+        let employees_by_month = BTreeMap::from([(1706745600, vec![chile, argentina])]);
+
+        let blocks =
+            new_employees_blocks(1704067200, 1709251199, &employees_by_month, true, Tz::UTC);
+
+        let titles = blocks
+            .iter()
+            .filter(|block| matches!(block, Block::Section { .. }))
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            titles,
+            vec![
+                Block::section("Los que entraron desde el *01/01/2024* hasta el *29/02/2024*:"),
+                Block::section("*Argentina*"),
+                Block::section("<@DEF456> DEF456"),
+                Block::section("*Chile*"),
+                Block::section("<@ABC123> ABC123"),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_show_dates_in_the_timezone() {
+        let mut employee = test_employee("ABC123", 1711933200); // 2024-04-01 01:00:00 UTC-0
+                                                                // A day, so the same wherever it's read.
+        employee.left_date = chrono::NaiveDate::from_ymd_opt(2024, 4, 1);
+        let employees_by_month = BTreeMap::from([(1709251200, vec![employee])]);
+
+        let blocks = new_employees_blocks(
+            1709262000, // 2024-03-01 03:00:00 UTC-0
+            1711940399, // 2024-04-01 02:59:59 UTC-0
+            &employees_by_month,
+            false,
+            America::Argentina::Buenos_Aires,
+        );
+
+        assert_eq!(
+            blocks[0],
+            Block::section("Los que entraron desde el *01/03/2024* hasta el *31/03/2024*:")
+        );
+        assert_eq!(blocks[2], Block::header("Marzo 2024"));
+        assert_eq!(
+            blocks[4],
+            Block::context(vec![
+                "Sin país".to_string(),
+                "Entró el 31/03/2024".to_string(),
+                "Se fue el 01/04/2024".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn should_say_when_nobody_joined() {
+        let blocks = new_employees_blocks(1704067200, 1709251199, &BTreeMap::new(), false, Tz::UTC);
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(
+            blocks[1],
+            Block::context(vec!["No entró nadie en ese período.".to_string()])
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_pagination_blocks {
+    use super::pagination_blocks;
+    use crate::slack_api::blocks::{Block, Element};
+
+    #[test]
+    fn should_only_link_existing_pages() {
+        let value_for = |page: usize| page.to_string();
+
+        assert_eq!(pagination_blocks(0, 1, "page", value_for), vec![]);
+        assert_eq!(
+            pagination_blocks(0, 3, "page", value_for)[2],
+            Block::Actions {
+                elements: vec![Element::button("Siguiente", "page", "1".to_string())]
+            }
+        );
+        assert_eq!(
+            pagination_blocks(1, 3, "page", value_for),
+            vec![
+                Block::Divider,
+                Block::context(vec!["Página 2 de 3".to_string()]),
+                Block::Actions {
+                    elements: vec![
+                        Element::button("Anterior", "page", "0".to_string()),
+                        Element::button("Siguiente", "page", "2".to_string())
+                    ]
+                },
+            ]
+        );
+        assert_eq!(
+            pagination_blocks(2, 3, "page", value_for)[2],
+            Block::Actions {
+                elements: vec![Element::button("Anterior", "page", "1".to_string())]
+            }
+        );
+    }
+}
@@ -1,69 +1,115 @@
-use chrono::{Datelike, LocalResult, TimeZone, Utc};
-
-use super::EmployeesByMonth;
-
-const SPANISH_MONTHS: [&str; 12] = [
-    "Enero",
-    "Febrero",
-    "Marzo",
-    "Abril",
-    "Mayo",
-    "Junio",
-    "Julio",
-    "Agosto",
-    "Septiembre",
-    "Octubre",
-    "Noviembre",
-    "Diciembre",
-];
-
-fn tag(id: &str) -> String {
+use chrono::{Datelike, LocalResult, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use super::{
+    group_employees_by_country::group_employees_by_country, EmployeesByMonth, SPANISH_MONTHS,
+};
+use crate::models::{
+    AnnouncementSettings, Buddy, ChecklistTask, Employee, Onboardees, Projects, TaskCompletion,
+};
+use crate::scheduler::CheckInStage;
+
+pub(super) fn tag(id: &str) -> String {
     format!("<@{}>", id)
 }
 
-fn employee_list(employee_ids: Vec<String>) -> String {
-    employee_ids
-        .iter()
-        .map(|u| format!("- {}", tag(u)))
+fn channel(id: &str) -> String {
+    format!("<#{}>", id)
+}
+
+/// Countries are stored lowercased, as derived from `tz_label`.
+pub(super) fn format_country(country: &str) -> String {
+    country
+        .split(' ')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+pub(super) fn format_date(date: NaiveDate) -> String {
+    date.format("%d/%m/%Y").to_string()
+}
+
+fn employee_list<'a>(employees: impl IntoIterator<Item = &'a Employee>) -> String {
+    employees
+        .into_iter()
+        .map(|e| match e.left_date {
+            Some(left_date) => format!("- {} (se fue el {})", tag(&e.id), format_date(left_date)),
+            None => format!("- {}", tag(&e.id)),
+        })
         .collect::<Vec<String>>()
         .join("\n")
 }
 
-fn employee_list_by_month(employees_by_month: EmployeesByMonth) -> String {
+/// "Febrero 2024" for the first second of February 2024.
+pub(super) fn format_month(month: i64) -> String {
+    Utc.timestamp_opt(month, 0)
+        .map(|d| {
+            let fmt_month = SPANISH_MONTHS.get(d.month0() as usize).unwrap();
+            let y = d.year();
+            format!("{} {}", fmt_month, y)
+        })
+        .unwrap()
+}
+
+/// "Argentina", or "Sin país" for people whose country is unknown.
+pub(super) fn country_label(country: Option<&str>) -> String {
+    country
+        .map(format_country)
+        .unwrap_or("Sin país".to_string())
+}
+
+fn employee_list_by_country(employees: &[Employee]) -> String {
+    group_employees_by_country(employees)
+        .into_iter()
+        .map(|(country, employees)| {
+            format!("*{}*\n", country_label(country)) + &employee_list(employees)
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn employee_list_by_month(employees_by_month: EmployeesByMonth, group_by_country: bool) -> String {
     employees_by_month
         .iter()
         .rev()
         .map(|(&month, employees)| {
-            let str_month = Utc
-                .timestamp_opt(month, 0)
-                .map(|d| {
-                    let fmt_month = SPANISH_MONTHS.get(d.month0() as usize).unwrap();
-                    let y = d.year();
-                    format!("{} {}", fmt_month, y)
-                })
-                .unwrap();
-
-            format!("{}:\n", str_month) + &employee_list(employees.clone())
+            let list = if group_by_country {
+                employee_list_by_country(employees)
+            } else {
+                employee_list(employees)
+            };
+            format!("{}:\n", format_month(month)) + &list
         })
         .collect::<Vec<String>>()
         .join("\n\n")
 }
 
+/// Dates are shown on the clocks of `tz`.
 pub fn new_employees_template(
     from_ts: i64,
     to_ts: i64,
     employees_by_month: EmployeesByMonth,
+    group_by_country: bool,
+    tz: Tz,
 ) -> String {
     let [from, to] = [from_ts, to_ts].map(|d| {
         Utc.timestamp_opt(d, 0)
-            .map(|d| d.format("%d/%m/%Y").to_string())
+            .map(|d| d.with_timezone(&tz).format("%d/%m/%Y").to_string())
     });
 
     match (from, to) {
         (LocalResult::Single(from), LocalResult::Single(to)) => {
             let base_template =
                 format!("Los que entraron desde el {} hasta el {} son: \n", from, to);
-            let employees_by_month_template = employee_list_by_month(employees_by_month);
+            let employees_by_month_template =
+                employee_list_by_month(employees_by_month, group_by_country);
 
             base_template + &employees_by_month_template
         }
@@ -71,11 +117,231 @@ pub fn new_employees_template(
     }
 }
 
+/// Fills `{usuario}` in the configured welcome message.
+pub fn welcome_template(template: &str, employee_id: &str) -> String {
+    template.replace("{usuario}", &tag(employee_id))
+}
+
+/// Fills `{usuario}` and `{pais}` in the configured announcement and appends
+/// the intro text, if any.
+pub fn announcement_template(
+    settings: &AnnouncementSettings,
+    employee_id: &str,
+    country: Option<&str>,
+) -> String {
+    let country = country
+        .map(format_country)
+        .unwrap_or("algún lugar del mundo".to_string());
+    let announcement = settings
+        .template
+        .replace("{usuario}", &tag(employee_id))
+        .replace("{pais}", &country);
+
+    match &settings.intro {
+        Some(intro) => format!("{}\n{}", announcement, intro),
+        None => announcement,
+    }
+}
+
+pub fn announcement_settings_template(settings: &AnnouncementSettings) -> String {
+    format!(
+        "Anuncios de nuevos ingresos: *{}*\nCanal: {}\nMensaje: {}\nIntro: {}",
+        if settings.enabled {
+            "activados"
+        } else {
+            "desactivados"
+        },
+        settings
+            .channel_id
+            .as_deref()
+            .map(channel)
+            .unwrap_or("sin configurar".to_string()),
+        settings.template,
+        settings.intro.as_deref().unwrap_or("sin intro")
+    )
+}
+
+/// Without `from_ts` the search starts with the first employee.
+/// Echoes the dates as typed, since they are only resolved where the user is
+/// once the search runs.
+pub fn searching_template(dates: &str) -> String {
+    format!("Buscando a los que entraron según \"{}\"...", dates)
+}
+
+pub fn employee_left_template(employee_id: &str, left_date: NaiveDate) -> String {
+    format!(
+        "Registré que {} se fue el {}. Ya no va a aparecer en /nuevos.",
+        tag(employee_id),
+        format_date(left_date)
+    )
+}
+
+pub fn employee_not_found_template(employee_id: &str) -> String {
+    format!("No encontré a {} entre los empleados.", tag(employee_id))
+}
+
+pub fn project_not_found_template(name: &str) -> String {
+    format!("No existe un proyecto llamado *{}*.", name)
+}
+
+pub fn project_created_template(project: &Projects) -> String {
+    format!(
+        "Creé el proyecto *{}* con {} como admin.",
+        project.name,
+        tag(&project.admin_id)
+    )
+}
+
+pub fn onboardee_added_template(project: &Projects, onboardee: &Onboardees) -> String {
+    format!(
+        "Agregué a {} a *{}* con fecha de onboarding {}.",
+        tag(&onboardee.employee_id),
+        project.name,
+        format_date(onboardee.onboarding_date)
+    )
+}
+
+pub fn project_onboardees_template(
+    project: &Projects,
+    onboardees: &[(Onboardees, Employee)],
+) -> String {
+    if onboardees.is_empty() {
+        return format!("*{}* todavía no tiene onboardees.", project.name);
+    }
+
+    let list = onboardees
+        .iter()
+        .map(|(o, _)| {
+            format!(
+                "- {} desde el {}",
+                tag(&o.employee_id),
+                format_date(o.onboarding_date)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!(
+        "Onboardees de *{}* (admin {}):\n{}",
+        project.name,
+        tag(&project.admin_id),
+        list
+    )
+}
+
+pub fn checklist_task_added_template(project: &Projects, task: &ChecklistTask) -> String {
+    format!(
+        "Agregué la tarea {}. {} al checklist de *{}*.",
+        task.position, task.title, project.name
+    )
+}
+
+pub fn checklist_progress_template(
+    project: &Projects,
+    employee_id: &str,
+    progress: &[(ChecklistTask, Option<TaskCompletion>)],
+) -> String {
+    if progress.is_empty() {
+        return format!("*{}* todavía no tiene checklist.", project.name);
+    }
+
+    let done = progress.iter().filter(|(_, c)| c.is_some()).count();
+    let list = progress
+        .iter()
+        .map(|(task, completion)| match completion {
+            Some(c) => format!(
+                ":white_check_mark: {}. {} ({})",
+                task.position,
+                task.title,
+                format_date(c.completed_at.date_naive())
+            ),
+            None => format!(":white_large_square: {}. {}", task.position, task.title),
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!(
+        "Checklist de {} en *{}* ({}/{}):\n{}",
+        tag(employee_id),
+        project.name,
+        done,
+        progress.len(),
+        list
+    )
+}
+
+pub fn check_in_template(stage: CheckInStage, employee_id: &str) -> String {
+    match stage {
+        CheckInStage::Day1 => format!(
+            "¡Hola {}! ¿Cómo fue tu primer día? Si necesitás algo, escribinos.",
+            tag(employee_id)
+        ),
+        CheckInStage::Week1 => format!(
+            "¡Ya pasó tu primera semana, {}! ¿Tenés todos los accesos que necesitás? Revisá tu `/checklist`.",
+            tag(employee_id)
+        ),
+        CheckInStage::Day30 => format!(
+            "¡Cumpliste tu primer mes, {}! Nos encantaría saber cómo te sentís hasta ahora.",
+            tag(employee_id)
+        ),
+    }
+}
+
+pub fn your_buddy_dm_template(buddy_id: &str) -> String {
+    format!(
+        "Tu buddy es {}. Escribile ante cualquier duda durante tu onboarding.",
+        tag(buddy_id)
+    )
+}
+
+pub fn buddy_assigned_dm_template(employee_id: &str) -> String {
+    format!(
+        "Vas a ser buddy de {}. ¡Dale una mano en sus primeros días!",
+        tag(employee_id)
+    )
+}
+
+pub fn buddy_assignment_template(buddy: &Buddy) -> String {
+    format!(
+        "Asigné a {} como buddy de {}.",
+        tag(&buddy.buddy_id),
+        tag(&buddy.employee_id)
+    )
+}
+
+pub fn buddies_template(employee_id: &str, buddy: Option<&Buddy>, mentees: &[Buddy]) -> String {
+    let buddy_line = match buddy {
+        Some(b) => format!(
+            "{} tiene como buddy a {} desde el {}.",
+            tag(employee_id),
+            tag(&b.buddy_id),
+            format_date(b.assigned_at.date_naive())
+        ),
+        None => format!("{} todavía no tiene buddy.", tag(employee_id)),
+    };
+
+    if mentees.is_empty() {
+        return buddy_line;
+    }
+
+    let mentee_list = mentees
+        .iter()
+        .map(|m| tag(&m.employee_id))
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("{}\nEs buddy de: {}.", buddy_line, mentee_list)
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
 
     use super::*;
+    use crate::models::test_employee;
+
+    fn employees(ids: &[&str], ts: i64) -> Vec<Employee> {
+        ids.iter().map(|id| test_employee(id, ts)).collect()
+    }
 
     #[test]
     fn test_tag() {
@@ -84,13 +350,20 @@ mod tests {
 
     #[test]
     fn test_employee_list() {
-        let employees = vec![
-            "ABC123".to_string(),
-            "DEF456".to_string(),
-            "GHI789".to_string(),
-        ];
+        let employees = employees(&["ABC123", "DEF456", "GHI789"], 1706745600);
         let expected = "- <@ABC123>\n- <@DEF456>\n- <@GHI789>";
-        assert_eq!(employee_list(employees), expected);
+        assert_eq!(employee_list(&employees), expected);
+    }
+
+    #[test]
+    fn test_employee_list_annotates_people_who_left() {
+        let mut left = test_employee("ABC123", 1706745600);
+        left.active = false;
+        left.left_date = chrono::NaiveDate::from_ymd_opt(2024, 3, 1);
+        let employees = vec![left, test_employee("DEF456", 1706745600)];
+
+        let expected = "- <@ABC123> (se fue el 01/03/2024)\n- <@DEF456>";
+        assert_eq!(employee_list(&employees), expected);
     }
 
     #[test]
@@ -99,14 +372,27 @@ mod tests {
         let jun_2030 = 1906502400;
         let feb_2024 = 1706745600;
         let mut employees_by_month = BTreeMap::new();
-        employees_by_month.insert(feb_2024 + one_week, vec!["ABC123".to_string()]);
+        employees_by_month.insert(feb_2024 + one_week, employees(&["ABC123"], feb_2024));
         employees_by_month.insert(
             jun_2030 + one_week,
-            vec!["DEF456".to_string(), "GHI789".to_string()],
+            employees(&["DEF456", "GHI789"], jun_2030),
         );
 
         let expected = "Junio 2030:\n- <@DEF456>\n- <@GHI789>\n\nFebrero 2024:\n- <@ABC123>";
-        assert_eq!(employee_list_by_month(employees_by_month), expected);
+        assert_eq!(employee_list_by_month(employees_by_month, false), expected);
+    }
+
+    #[test]
+    fn test_employee_list_by_month_and_country() {
+        let feb_2024 = 1706745600;
+        let mut staff = employees(&["ABC123", "DEF456", "GHI789"], feb_2024);
+        staff[0].country = Some("chile".to_string());
+        staff[2].country = Some("costa rica".to_string());
+        let employees_by_month = BTreeMap::from([(feb_2024, staff)]);
+
+        let expected =
+            "Febrero 2024:\n*Sin país*\n- <@DEF456>\n*Chile*\n- <@ABC123>\n*Costa Rica*\n- <@GHI789>";
+        assert_eq!(employee_list_by_month(employees_by_month, true), expected);
     }
 
     #[test]
@@ -115,18 +401,179 @@ mod tests {
         let jun_2030 = 1906502400;
         let feb_2024 = 1706745600;
         let mut employees_by_month = BTreeMap::new();
-        employees_by_month.insert(feb_2024 + one_week, vec!["ABC123".to_string()]);
+        employees_by_month.insert(feb_2024 + one_week, employees(&["ABC123"], feb_2024));
         employees_by_month.insert(
             jun_2030 + one_week,
-            vec!["DEF456".to_string(), "GHI789".to_string()],
+            employees(&["DEF456", "GHI789"], jun_2030),
         );
 
         let from_ts = 1612137600; // 2021-02-01 00:00:00 UTC-0
         let to_ts = 1906502400; // 2030-06-01 00:00:00 UTC-0
-        let result = new_employees_template(from_ts, to_ts, employees_by_month);
+        let result = new_employees_template(from_ts, to_ts, employees_by_month, false, Tz::UTC);
 
         let expected = "Los que entraron desde el 01/02/2021 hasta el 01/06/2030 son: \nJunio 2030:\n- <@DEF456>\n- <@GHI789>\n\nFebrero 2024:\n- <@ABC123>";
 
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_templates_show_dates_in_the_timezone() {
+        let from_ts = 1711854000; // 2024-03-31 03:00:00 UTC-0
+        let to_ts = 1711940399; // 2024-04-01 02:59:59 UTC-0
+        let tz = chrono_tz::America::Argentina::Buenos_Aires;
+
+        assert_eq!(
+            new_employees_template(from_ts, to_ts, BTreeMap::new(), false, tz),
+            "Los que entraron desde el 31/03/2024 hasta el 31/03/2024 son: \n"
+        );
+    }
+
+    #[test]
+    fn test_searching_template_echoes_the_dates() {
+        assert_eq!(
+            searching_template("este mes"),
+            "Buscando a los que entraron según \"este mes\"..."
+        );
+    }
+
+    #[test]
+    fn test_welcome_template() {
+        assert_eq!(
+            welcome_template("¡Hola {usuario}! Bienvenida {usuario}", "ABC123"),
+            "¡Hola <@ABC123>! Bienvenida <@ABC123>"
+        );
+        assert_eq!(welcome_template("Hola", "ABC123"), "Hola");
+    }
+
+    fn announcement_settings(intro: Option<&str>) -> AnnouncementSettings {
+        AnnouncementSettings {
+            id: 1,
+            enabled: true,
+            channel_id: Some("C123".to_string()),
+            template: "Bienvenida {usuario}, desde {pais}".to_string(),
+            intro: intro.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_format_country() {
+        assert_eq!(format_country("argentina"), "Argentina");
+        assert_eq!(format_country("central european"), "Central European");
+    }
+
+    #[test]
+    fn test_announcement_template() {
+        assert_eq!(
+            announcement_template(&announcement_settings(None), "ABC123", Some("chile")),
+            "Bienvenida <@ABC123>, desde Chile"
+        );
+        assert_eq!(
+            announcement_template(
+                &announcement_settings(Some("Se suma al equipo de Pagos.")),
+                "ABC123",
+                None
+            ),
+            "Bienvenida <@ABC123>, desde algún lugar del mundo\nSe suma al equipo de Pagos."
+        );
+    }
+
+    #[test]
+    fn test_announcement_settings_template() {
+        assert_eq!(
+            announcement_settings_template(&announcement_settings(None)),
+            "Anuncios de nuevos ingresos: *activados*\nCanal: <#C123>\nMensaje: Bienvenida {usuario}, desde {pais}\nIntro: sin intro"
+        );
+    }
+
+    fn project() -> Projects {
+        Projects {
+            id: uuid::Uuid::nil(),
+            name: "Pagos".to_string(),
+            admin_id: "ADM001".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_project_onboardees_template() {
+        let onboardee = Onboardees {
+            project_id: uuid::Uuid::nil(),
+            employee_id: "ABC123".to_string(),
+            onboarding_date: chrono::NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+        };
+        let employee = test_employee("ABC123", 1706745600);
+
+        let result = project_onboardees_template(&project(), &[(onboardee, employee)]);
+
+        assert_eq!(
+            result,
+            "Onboardees de *Pagos* (admin <@ADM001>):\n- <@ABC123> desde el 01/02/2024"
+        );
+    }
+
+    fn buddy(employee_id: &str, buddy_id: &str) -> Buddy {
+        Buddy {
+            employee_id: employee_id.to_string(),
+            buddy_id: buddy_id.to_string(),
+            assigned_at: chrono::DateTime::from_timestamp(1706745600, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_buddies_template() {
+        let own = buddy("ABC123", "DEF456");
+        let mentees = [buddy("GHI789", "ABC123"), buddy("JKL012", "ABC123")];
+
+        assert_eq!(
+            buddies_template("ABC123", Some(&own), &mentees),
+            "<@ABC123> tiene como buddy a <@DEF456> desde el 01/02/2024.\nEs buddy de: <@GHI789>, <@JKL012>."
+        );
+        assert_eq!(
+            buddies_template("ABC123", None, &[]),
+            "<@ABC123> todavía no tiene buddy."
+        );
+    }
+
+    fn checklist_task(position: i32, title: &str) -> ChecklistTask {
+        ChecklistTask {
+            id: uuid::Uuid::nil(),
+            project_id: uuid::Uuid::nil(),
+            title: title.to_string(),
+            position,
+        }
+    }
+
+    #[test]
+    fn test_checklist_progress_template() {
+        let completion = TaskCompletion {
+            task_id: uuid::Uuid::nil(),
+            employee_id: "ABC123".to_string(),
+            completed_by: "ABC123".to_string(),
+            completed_at: chrono::DateTime::from_timestamp(1706745600, 0).unwrap(),
+        };
+        let progress = vec![
+            (checklist_task(1, "Acceso al repo"), Some(completion)),
+            (checklist_task(2, "Primer PR"), None),
+        ];
+
+        let result = checklist_progress_template(&project(), "ABC123", &progress);
+
+        assert_eq!(
+            result,
+            "Checklist de <@ABC123> en *Pagos* (1/2):\n:white_check_mark: 1. Acceso al repo (01/02/2024)\n:white_large_square: 2. Primer PR"
+        );
+    }
+
+    #[test]
+    fn test_checklist_progress_template_without_tasks() {
+        let result = checklist_progress_template(&project(), "ABC123", &[]);
+
+        assert_eq!(result, "*Pagos* todavía no tiene checklist.");
+    }
+
+    #[test]
+    fn test_project_onboardees_template_without_onboardees() {
+        let result = project_onboardees_template(&project(), &[]);
+
+        assert_eq!(result, "*Pagos* todavía no tiene onboardees.");
+    }
 }
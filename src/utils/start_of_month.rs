@@ -1,7 +1,7 @@
-use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use chrono::{DateTime, Datelike, NaiveDate};
 
 pub fn start_of_month(ts: i64) -> i64 {
-    NaiveDateTime::from_timestamp_opt(ts, 0)
+    DateTime::from_timestamp(ts, 0)
         .map(|d| NaiveDate::from_ymd_opt(d.year(), d.month(), 1).unwrap())
         .map(|d| d.and_hms_opt(0, 0, 0).unwrap())
         .unwrap()